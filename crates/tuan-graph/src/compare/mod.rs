@@ -0,0 +1,182 @@
+use crate::{
+    cycles::CycleOptions,
+    graph::{EdgeKind, Graph, NodeId},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+/// An import between two project-relative paths. Imports of the same kind between the same
+/// files are merged, so that moving an import around a file is not reported.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct PathEdge {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub kind: EdgeKind,
+}
+
+/// How a graph changed between two snapshots. Every path is project-relative and every list
+/// is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphComparison {
+    pub added_files: Vec<PathBuf>,
+    pub removed_files: Vec<PathBuf>,
+    pub added_edges: Vec<PathEdge>,
+    pub removed_edges: Vec<PathEdge>,
+    /// Representative path of every cycle whose files did not already form a cycle before.
+    pub new_cycles: Vec<Vec<PathBuf>>,
+}
+
+impl GraphComparison {
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.new_cycles.is_empty()
+    }
+}
+
+impl Graph {
+    /// Compares `self`, built from `root`, with the newer `other`, built from `other_root`.
+    /// Files are matched by project-relative path, so both graphs may come from different
+    /// checkouts of the same project.
+    pub fn compare(
+        &self,
+        root: &Path,
+        other: &Graph,
+        other_root: &Path,
+        cycle_options: CycleOptions,
+    ) -> GraphComparison {
+        let before = Snapshot::new(self, root, cycle_options);
+        let after = Snapshot::new(other, other_root, cycle_options);
+
+        let mut new_cycles = after
+            .cycles
+            .into_iter()
+            .filter(|(members, _)| !before.cycles.contains_key(members))
+            .map(|(_, path)| path)
+            .collect::<Vec<_>>();
+        new_cycles.sort();
+
+        GraphComparison {
+            added_files: after.files.difference(&before.files).cloned().collect(),
+            removed_files: before.files.difference(&after.files).cloned().collect(),
+            added_edges: after.edges.difference(&before.edges).cloned().collect(),
+            removed_edges: before.edges.difference(&after.edges).cloned().collect(),
+            new_cycles,
+        }
+    }
+}
+
+struct Snapshot {
+    files: BTreeSet<PathBuf>,
+    edges: BTreeSet<PathEdge>,
+    /// Representative path of every cycle, keyed by its members.
+    cycles: HashMap<BTreeSet<PathBuf>, Vec<PathBuf>>,
+}
+
+impl Snapshot {
+    fn new(graph: &Graph, root: &Path, cycle_options: CycleOptions) -> Self {
+        let paths = graph
+            .nodes
+            .values()
+            .map(|node| {
+                let relative_path = node.file_path.strip_prefix(root).unwrap_or(&node.file_path);
+                (node.id, relative_path.to_path_buf())
+            })
+            .collect::<HashMap<NodeId, PathBuf>>();
+
+        let edges = graph
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                Some(PathEdge {
+                    from: paths.get(&edge.from)?.clone(),
+                    to: paths.get(&edge.to)?.clone(),
+                    kind: edge.kind,
+                })
+            })
+            .collect();
+        let cycles = graph
+            .cycles(cycle_options)
+            .into_iter()
+            .map(|cycle| {
+                let members = cycle.members.iter().map(|id| paths[id].clone()).collect();
+                let path = cycle.path.iter().map(|id| paths[id].clone()).collect();
+                (members, path)
+            })
+            .collect();
+
+        Self {
+            files: paths.into_values().collect(),
+            edges,
+            cycles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node, test_node};
+    use test_log::test;
+
+    fn graph(root: &Path, files: &[&str], edges: &[(&str, &str)]) -> Graph {
+        let mut graph = Graph::new();
+        for file in files {
+            let file_path = root.join(file);
+            graph.add_node(test_node(Node::id_for(root, &file_path), file_path));
+        }
+        let id = |path: &str| Node::id_for(root, &root.join(path));
+        for (from, to) in edges {
+            graph.add_edge(Edge::new(id(from), id(to), EdgeKind::Static));
+        }
+        graph
+    }
+
+    #[test]
+    fn it_compares_graphs_by_relative_path() {
+        let (base_root, head_root) = (Path::new("/base"), Path::new("/head"));
+        let base = graph(
+            base_root,
+            &["a.ts", "b.ts", "c.ts", "old.ts"],
+            &[("a.ts", "b.ts"), ("b.ts", "old.ts")],
+        );
+        let head = graph(
+            head_root,
+            &["a.ts", "b.ts", "c.ts", "new.ts"],
+            &[("a.ts", "b.ts"), ("b.ts", "c.ts"), ("c.ts", "a.ts")],
+        );
+
+        let comparison = base.compare(base_root, &head, head_root, CycleOptions::default());
+
+        assert_eq!(comparison.added_files, vec![PathBuf::from("new.ts")]);
+        assert_eq!(comparison.removed_files, vec![PathBuf::from("old.ts")]);
+        assert_eq!(
+            comparison.added_edges,
+            vec![
+                PathEdge {
+                    from: "b.ts".into(),
+                    to: "c.ts".into(),
+                    kind: EdgeKind::Static
+                },
+                PathEdge {
+                    from: "c.ts".into(),
+                    to: "a.ts".into(),
+                    kind: EdgeKind::Static
+                },
+            ]
+        );
+        assert_eq!(comparison.removed_edges.len(), 1);
+        assert_eq!(comparison.new_cycles.len(), 1);
+        assert_eq!(comparison.new_cycles[0].len(), 3);
+
+        assert!(
+            head.compare(head_root, &head, head_root, CycleOptions::default())
+                .is_empty()
+        );
+    }
+}
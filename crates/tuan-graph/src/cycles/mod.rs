@@ -0,0 +1,253 @@
+use crate::graph::{Edge, EdgeKind, Graph, NodeId};
+use std::collections::{HashMap, HashSet, VecDeque, hash_map::Entry};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CycleOptions {
+    /// Skip `import type` edges, which are erased at runtime.
+    pub ignore_type_only: bool,
+    /// Skip `import()` edges, which are only evaluated once the module finished loading.
+    pub ignore_dynamic: bool,
+}
+
+impl CycleOptions {
+    fn includes(&self, edge: &Edge) -> bool {
+        match edge.kind {
+            EdgeKind::TypeOnly => !self.ignore_type_only,
+            EdgeKind::Dynamic => !self.ignore_dynamic,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cycle {
+    /// Every file of the strongly connected component, sorted by id.
+    pub members: Vec<NodeId>,
+    /// Shortest cycle going through the first member. The last file imports the first one.
+    pub path: Vec<NodeId>,
+    /// Import edges between consecutive files of `path`.
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Strongly connected components made of more than one file, or of a file importing
+    /// itself, each with a short representative cycle.
+    pub fn cycles(&self, options: CycleOptions) -> Vec<Cycle> {
+        let edges = self
+            .edges
+            .iter()
+            .filter(|edge| options.includes(edge))
+            .collect::<Vec<_>>();
+
+        let mut adjacency: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for edge in &edges {
+            adjacency.entry(edge.from).or_default().push(edge.to);
+        }
+        for neighbors in adjacency.values_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+
+        let mut nodes = self.nodes.keys().copied().collect::<Vec<_>>();
+        nodes.sort_unstable();
+
+        let mut cycles = strongly_connected_components(&nodes, &adjacency)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || adjacency
+                        .get(&component[0])
+                        .is_some_and(|neighbors| neighbors.contains(&component[0]))
+            })
+            .map(|mut members| {
+                members.sort_unstable();
+                let path = shortest_cycle(members[0], &members, &adjacency);
+                let mut cycle_edges = path
+                    .iter()
+                    .zip(path.iter().cycle().skip(1))
+                    .flat_map(|(&from, &to)| {
+                        let mut hop = edges
+                            .iter()
+                            .filter(|edge| edge.from == from && edge.to == to)
+                            .map(|edge| (*edge).clone())
+                            .collect::<Vec<_>>();
+                        hop.sort_by_key(|edge| edge.location.map(|location| location.start));
+                        hop
+                    })
+                    .collect::<Vec<_>>();
+                cycle_edges.dedup();
+                Cycle {
+                    members,
+                    path,
+                    edges: cycle_edges,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cycles.sort_by_key(|cycle| cycle.members[0]);
+        cycles
+    }
+}
+
+/// Iterative Tarjan, so that long import chains cannot overflow the stack.
+pub(crate) fn strongly_connected_components(
+    nodes: &[NodeId],
+    adjacency: &HashMap<NodeId, Vec<NodeId>>,
+) -> Vec<Vec<NodeId>> {
+    let mut index = HashMap::new();
+    let mut lowlink = HashMap::new();
+    let mut stack = Vec::new();
+    let mut on_stack = HashSet::new();
+    let mut components = Vec::new();
+
+    for &root in nodes {
+        if index.contains_key(&root) {
+            continue;
+        }
+
+        let mut call_stack = vec![(root, 0usize)];
+        index.insert(root, index.len());
+        lowlink.insert(root, index[&root]);
+        stack.push(root);
+        on_stack.insert(root);
+
+        while let Some((node, next_idx)) = call_stack.last_mut() {
+            let node = *node;
+            let neighbors = adjacency.get(&node).map_or(&[][..], Vec::as_slice);
+
+            if let Some(&next) = neighbors.get(*next_idx) {
+                *next_idx += 1;
+                if !index.contains_key(&next) {
+                    index.insert(next, index.len());
+                    lowlink.insert(next, index[&next]);
+                    stack.push(next);
+                    on_stack.insert(next);
+                    call_stack.push((next, 0));
+                } else if on_stack.contains(&next) {
+                    let low = lowlink[&node].min(index[&next]);
+                    lowlink.insert(node, low);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                let low = lowlink[&parent].min(lowlink[&node]);
+                lowlink.insert(parent, low);
+            }
+            if lowlink[&node] == index[&node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Breadth-first search from `start` back to itself, staying inside the component.
+fn shortest_cycle(
+    start: NodeId,
+    members: &[NodeId],
+    adjacency: &HashMap<NodeId, Vec<NodeId>>,
+) -> Vec<NodeId> {
+    let members = members.iter().collect::<HashSet<_>>();
+    let mut previous = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        for &next in adjacency.get(&current).into_iter().flatten() {
+            if next == start {
+                let mut path = vec![current];
+                let mut node = current;
+                while node != start {
+                    node = previous[&node];
+                    path.push(node);
+                }
+                path.reverse();
+                return path;
+            }
+            if members.contains(&next)
+                && let Entry::Vacant(entry) = previous.entry(next)
+            {
+                entry.insert(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    vec![start]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_node;
+    use test_log::test;
+
+    fn graph(edges: &[(NodeId, NodeId, EdgeKind)]) -> Graph {
+        let mut graph = Graph::new();
+        for id in 1..=6 {
+            graph.add_node(test_node(id, format!("/project/{id}.ts")));
+        }
+        for &(from, to, kind) in edges {
+            graph.add_edge(Edge::new(from, to, kind));
+        }
+        graph
+    }
+
+    #[test]
+    fn it_finds_cycles_with_a_representative_path() {
+        let graph = graph(&[
+            (1, 2, EdgeKind::Static),
+            (2, 3, EdgeKind::Static),
+            (3, 4, EdgeKind::Static),
+            (4, 1, EdgeKind::Static),
+            (2, 1, EdgeKind::Require),
+            (5, 5, EdgeKind::Static),
+            (5, 6, EdgeKind::Static),
+        ]);
+
+        let cycles = graph.cycles(CycleOptions::default());
+
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].members, vec![1, 2, 3, 4]);
+        assert_eq!(cycles[0].path, vec![1, 2]);
+        assert_eq!(
+            cycles[0].edges,
+            vec![
+                Edge::new(1, 2, EdgeKind::Static),
+                Edge::new(2, 1, EdgeKind::Require)
+            ]
+        );
+        assert_eq!(cycles[1].members, vec![5]);
+        assert_eq!(cycles[1].path, vec![5]);
+    }
+
+    #[test]
+    fn it_ignores_type_only_and_dynamic_edges() {
+        let graph = graph(&[
+            (1, 2, EdgeKind::Static),
+            (2, 1, EdgeKind::TypeOnly),
+            (3, 4, EdgeKind::Static),
+            (4, 3, EdgeKind::Dynamic),
+        ]);
+
+        assert_eq!(graph.cycles(CycleOptions::default()).len(), 2);
+        assert!(
+            graph
+                .cycles(CycleOptions {
+                    ignore_type_only: true,
+                    ignore_dynamic: true,
+                })
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::graph::SourceLocation;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    /// The resolver could not find the imported file.
+    UnresolvedImport,
+    /// The file has syntax errors, so some or all of its imports may be missing.
+    ParseError,
+    /// The file could not be read.
+    ReadError,
+}
+
+/// A problem found while building a graph, which usually means missing nodes or edges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    pub file_path: PathBuf,
+    pub location: Option<SourceLocation>,
+    /// The import specifier, for unresolved imports.
+    pub specifier: Option<String>,
+    /// The resolver, parser or I/O error.
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Relative and absolute imports that do not resolve are errors. Bare specifiers are only
+    /// warnings, since they also cover packages that are not installed. Node.js builtins are
+    /// never reported.
+    pub fn unresolved_import(
+        file_path: PathBuf,
+        location: SourceLocation,
+        specifier: &str,
+        message: String,
+    ) -> Self {
+        let severity = if specifier.starts_with('.') || specifier.starts_with('/') {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        Self {
+            kind: DiagnosticKind::UnresolvedImport,
+            severity,
+            file_path,
+            location: Some(location),
+            specifier: Some(specifier.to_string()),
+            message,
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{
+    cluster::Cluster,
+    graph::{Edge, Graph, Node, NodeId},
+};
+use serde_json::json;
+use std::{collections::HashMap, fmt::Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Graphviz
+    Dot,
+    /// yEd, Cytoscape
+    GraphMl,
+    /// Gephi
+    Gexf,
+    /// <https://jsongraphformat.info>
+    JsonGraph,
+}
+
+impl Graph {
+    /// Serializes the graph with node labels, file paths, positions and edge kinds. Cluster
+    /// membership is included when `clusters` (from [`Graph::clusterize`]) is given.
+    pub fn export(&self, format: ExportFormat, clusters: Option<&[Cluster]>) -> String {
+        match format {
+            ExportFormat::Dot => self.to_dot(clusters),
+            ExportFormat::GraphMl => self.to_graphml(clusters),
+            ExportFormat::Gexf => self.to_gexf(clusters),
+            ExportFormat::JsonGraph => self.to_json_graph(clusters),
+        }
+    }
+
+    pub fn to_dot(&self, clusters: Option<&[Cluster]>) -> String {
+        let cluster_of = cluster_membership(clusters);
+        let mut out = String::from("digraph {\n");
+
+        let node_line = |node: &Node| {
+            let mut attributes = format!(
+                "label=\"{}\", path=\"{}\", pos=\"{},{}\"",
+                escape_dot(&node.label),
+                escape_dot(&node.file_path.to_string_lossy()),
+                node.position.0,
+                node.position.1
+            );
+            if let Some(cluster) = cluster_of.get(&node.id) {
+                write!(attributes, ", cluster={cluster}").unwrap();
+            }
+            format!("\"{}\" [{}];\n", node.id, attributes)
+        };
+
+        match clusters {
+            Some(clusters) => {
+                for cluster in clusters {
+                    writeln!(out, "  subgraph cluster_{} {{", cluster.id).unwrap();
+                    for node in cluster.members.iter().filter_map(|id| self.nodes.get(id)) {
+                        write!(out, "    {}", node_line(node)).unwrap();
+                    }
+                    out.push_str("  }\n");
+                }
+                for node in sorted_nodes(self) {
+                    if !cluster_of.contains_key(&node.id) {
+                        write!(out, "  {}", node_line(node)).unwrap();
+                    }
+                }
+            }
+            None => {
+                for node in sorted_nodes(self) {
+                    write!(out, "  {}", node_line(node)).unwrap();
+                }
+            }
+        }
+
+        for edge in sorted_edges(self) {
+            writeln!(
+                out,
+                "  \"{}\" -> \"{}\" [kind=\"{}\"];",
+                edge.from,
+                edge.to,
+                edge.kind.as_str()
+            )
+            .unwrap();
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_graphml(&self, clusters: Option<&[Cluster]>) -> String {
+        let cluster_of = cluster_membership(clusters);
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"path\" for=\"node\" attr.name=\"path\" attr.type=\"string\"/>\n",
+            "  <key id=\"x\" for=\"node\" attr.name=\"x\" attr.type=\"double\"/>\n",
+            "  <key id=\"y\" for=\"node\" attr.name=\"y\" attr.type=\"double\"/>\n",
+            "  <key id=\"cluster\" for=\"node\" attr.name=\"cluster\" attr.type=\"long\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <graph id=\"G\" edgedefault=\"directed\">\n",
+        ));
+
+        for node in sorted_nodes(self) {
+            writeln!(out, "    <node id=\"n{}\">", node.id).unwrap();
+            writeln!(
+                out,
+                "      <data key=\"label\">{}</data>",
+                escape_xml(&node.label)
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "      <data key=\"path\">{}</data>",
+                escape_xml(&node.file_path.to_string_lossy())
+            )
+            .unwrap();
+            writeln!(out, "      <data key=\"x\">{}</data>", node.position.0).unwrap();
+            writeln!(out, "      <data key=\"y\">{}</data>", node.position.1).unwrap();
+            if let Some(cluster) = cluster_of.get(&node.id) {
+                writeln!(out, "      <data key=\"cluster\">{cluster}</data>").unwrap();
+            }
+            out.push_str("    </node>\n");
+        }
+
+        for (idx, edge) in sorted_edges(self).into_iter().enumerate() {
+            writeln!(
+                out,
+                "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">",
+                idx, edge.from, edge.to
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "      <data key=\"kind\">{}</data>",
+                edge.kind.as_str()
+            )
+            .unwrap();
+            out.push_str("    </edge>\n");
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    pub fn to_gexf(&self, clusters: Option<&[Cluster]>) -> String {
+        let cluster_of = cluster_membership(clusters);
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">\n",
+            "  <graph defaultedgetype=\"directed\">\n",
+            "    <attributes class=\"node\">\n",
+            "      <attribute id=\"path\" title=\"path\" type=\"string\"/>\n",
+            "      <attribute id=\"cluster\" title=\"cluster\" type=\"long\"/>\n",
+            "    </attributes>\n",
+            "    <attributes class=\"edge\">\n",
+            "      <attribute id=\"kind\" title=\"kind\" type=\"string\"/>\n",
+            "    </attributes>\n",
+            "    <nodes>\n",
+        ));
+
+        for node in sorted_nodes(self) {
+            writeln!(
+                out,
+                "      <node id=\"{}\" label=\"{}\">",
+                node.id,
+                escape_xml(&node.label)
+            )
+            .unwrap();
+            out.push_str("        <attvalues>\n");
+            writeln!(
+                out,
+                "          <attvalue for=\"path\" value=\"{}\"/>",
+                escape_xml(&node.file_path.to_string_lossy())
+            )
+            .unwrap();
+            if let Some(cluster) = cluster_of.get(&node.id) {
+                writeln!(
+                    out,
+                    "          <attvalue for=\"cluster\" value=\"{cluster}\"/>"
+                )
+                .unwrap();
+            }
+            out.push_str("        </attvalues>\n");
+            writeln!(
+                out,
+                "        <viz:position x=\"{}\" y=\"{}\" z=\"0\"/>",
+                node.position.0, node.position.1
+            )
+            .unwrap();
+            out.push_str("      </node>\n");
+        }
+
+        out.push_str("    </nodes>\n    <edges>\n");
+        for (idx, edge) in sorted_edges(self).into_iter().enumerate() {
+            writeln!(
+                out,
+                "      <edge id=\"{}\" source=\"{}\" target=\"{}\">",
+                idx, edge.from, edge.to
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "        <attvalues><attvalue for=\"kind\" value=\"{}\"/></attvalues>",
+                edge.kind.as_str()
+            )
+            .unwrap();
+            out.push_str("      </edge>\n");
+        }
+
+        out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+        out
+    }
+
+    pub fn to_json_graph(&self, clusters: Option<&[Cluster]>) -> String {
+        let cluster_of = cluster_membership(clusters);
+
+        let nodes = sorted_nodes(self)
+            .into_iter()
+            .map(|node| {
+                let mut metadata = json!({
+                    "filePath": node.file_path,
+                    "position": { "x": node.position.0, "y": node.position.1 },
+                });
+                if let Some(cluster) = cluster_of.get(&node.id) {
+                    metadata["cluster"] = json!(cluster);
+                }
+                (
+                    node.id.to_string(),
+                    json!({ "label": node.label, "metadata": metadata }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        let edges = sorted_edges(self)
+            .into_iter()
+            .map(|edge| {
+                json!({
+                    "source": edge.from.to_string(),
+                    "target": edge.to.to_string(),
+                    "relation": edge.kind.as_str(),
+                    "directed": true,
+                    "metadata": {
+                        "symbols": edge.symbols,
+                        "location": edge.location,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "graph": {
+                "directed": true,
+                "nodes": nodes,
+                "edges": edges,
+            }
+        })
+        .to_string()
+    }
+}
+
+fn cluster_membership(clusters: Option<&[Cluster]>) -> HashMap<NodeId, usize> {
+    clusters
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|cluster| cluster.members.iter().map(|&id| (id, cluster.id)))
+        .collect()
+}
+
+fn sorted_nodes(graph: &Graph) -> Vec<&Node> {
+    let mut nodes = graph.iter_nodes().collect::<Vec<_>>();
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+fn sorted_edges(graph: &Graph) -> Vec<&Edge> {
+    let mut edges = graph.iter_edges().collect::<Vec<_>>();
+    edges.sort_by_key(|edge| {
+        (
+            edge.from,
+            edge.to,
+            edge.kind.as_str(),
+            edge.location.map(|location| location.start),
+        )
+    });
+    edges
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{EdgeKind, test_node};
+    use ordered_float::OrderedFloat;
+    use test_log::test;
+
+    fn node(id: NodeId, path: &str) -> Node {
+        Node {
+            position: (OrderedFloat(1.5), OrderedFloat(-2.0)),
+            ..test_node(id, path)
+        }
+    }
+
+    fn graph() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_node(node(1, "/project/a.ts"));
+        graph.add_node(node(2, "/project/b&c.ts"));
+        graph.add_edge(Edge::new(1, 2, EdgeKind::TypeOnly));
+        graph
+    }
+
+    #[test]
+    fn it_exports_to_dot_with_clusters() {
+        let clusters = [Cluster {
+            id: 0,
+            members: vec![1],
+        }];
+        let dot = graph().to_dot(Some(&clusters));
+
+        assert!(dot.starts_with("digraph {\n  subgraph cluster_0 {\n"));
+        assert!(dot.contains(
+            "    \"1\" [label=\"a.ts\", path=\"/project/a.ts\", pos=\"1.5,-2\", cluster=0];"
+        ));
+        assert!(dot.contains("  \"1\" -> \"2\" [kind=\"type-only\"];"));
+    }
+
+    #[test]
+    fn it_escapes_xml_formats() {
+        let graph = graph();
+
+        assert!(
+            graph
+                .to_graphml(None)
+                .contains("<data key=\"label\">b&amp;c.ts</data>")
+        );
+        assert!(graph.to_gexf(None).contains("label=\"b&amp;c.ts\""));
+    }
+
+    #[test]
+    fn it_exports_to_json_graph() {
+        let value: serde_json::Value = serde_json::from_str(&graph().to_json_graph(None)).unwrap();
+
+        assert_eq!(value["graph"]["nodes"]["2"]["label"], "b&c.ts");
+        assert_eq!(value["graph"]["edges"][0]["relation"], "type-only");
+        assert_eq!(
+            value["graph"]["nodes"]["1"]["metadata"]["position"]["x"],
+            1.5
+        );
+    }
+}
@@ -1,14 +1,13 @@
 use ordered_float::OrderedFloat;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{
     collections::{HashMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 #[repr(C)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Graph {
     pub edges: HashSet<Edge>,
     pub nodes: HashMap<NodeId, Node>,
@@ -30,6 +29,40 @@ impl Graph {
         self.edges.insert(edge);
     }
 
+    /// Removes the node along with every edge pointing from or to it.
+    pub fn remove_node(&mut self, id: NodeId) -> Option<(Node, Vec<Edge>)> {
+        let node = self.nodes.remove(&id)?;
+        let edges = self
+            .edges
+            .iter()
+            .filter(|edge| edge.from == id || edge.to == id)
+            .cloned()
+            .collect::<Vec<_>>();
+        for edge in &edges {
+            self.edges.remove(edge);
+        }
+        Some((node, edges))
+    }
+
+    pub fn remove_edge(&mut self, edge: &Edge) -> bool {
+        self.edges.remove(edge)
+    }
+
+    pub fn apply(&mut self, diff: &GraphDiff) {
+        for edge in &diff.removed_edges {
+            self.remove_edge(edge);
+        }
+        for node in &diff.removed_nodes {
+            self.remove_node(node.id);
+        }
+        for node in &diff.added_nodes {
+            self.add_node(node.clone());
+        }
+        for edge in &diff.added_edges {
+            self.add_edge(edge.clone());
+        }
+    }
+
     pub fn iter_nodes(&self) -> impl Iterator<Item = &Node> {
         self.nodes.values()
     }
@@ -79,40 +112,81 @@ impl Graph {
     }
 }
 
-pub type NodeId = usize;
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<Node>,
+    pub removed_nodes: Vec<Node>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<Edge>,
+}
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize)]
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Derived from the project-relative path of the file, see [`Node::id_for`].
+pub type NodeId = u64;
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileFingerprint {
     size: u64,
     modified_ns: u128,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+impl FileFingerprint {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        let md = file.metadata().ok()?;
+        Some(Self {
+            size: md.len(),
+            modified_ns: md
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_nanos(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Node {
     pub id: NodeId,
     pub label: String,
     pub file_path: PathBuf,
     pub position: (OrderedFloat<f64>, OrderedFloat<f64>),
-    pub key: FileFingerprint,
+    pub fingerprint: FileFingerprint,
+    /// Set by [`Graph::mark_unreachable`] when no entry point leads to this file.
+    #[serde(default)]
+    pub dead: bool,
+    #[serde(default)]
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodeKind {
+    /// A file of the project.
+    #[default]
+    Source,
+    /// An npm package imported from `node_modules`. Its `file_path` is the package directory.
+    ExternalPackage,
+    /// A CSS, SCSS, Sass or Less file of the project.
+    Stylesheet,
+    /// A non-code file imported by the project, e.g. an image, a font or a JSON file.
+    Asset,
 }
 
 impl Node {
-    pub fn from_path(file_path: PathBuf) -> Option<Self> {
-        static NODE_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
-        let id = NODE_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    pub fn from_path(project_root: &Path, file_path: PathBuf) -> Option<Self> {
+        let id = Self::id_for(project_root, &file_path);
 
-        let file = File::open(&file_path).ok()?;
-        let md = file.metadata().ok()?;
-        let key = FileFingerprint {
-            size: md.len(),
-            modified_ns: md
-                .modified()
-                .ok()?
-                .duration_since(std::time::UNIX_EPOCH)
-                .ok()?
-                .as_nanos(),
-        };
-        drop(file);
+        let fingerprint = FileFingerprint::from_path(&file_path)?;
 
         let label = file_path
             .file_name()
@@ -124,19 +198,177 @@ impl Node {
             label,
             file_path,
             position: (OrderedFloat(0.0), OrderedFloat(0.0)),
-            key,
+            fingerprint,
+            dead: false,
+            kind: NodeKind::Source,
         })
     }
+
+    /// Hashes the project-relative path (with `/` separators) so the same file gets the
+    /// same ID across builds, processes and machines. The hash is FNV-1a truncated to
+    /// 53 bits, so that IDs survive the round-trip through JavaScript numbers.
+    pub fn id_for(project_root: &Path, file_path: &Path) -> NodeId {
+        hash_id(&relative_key(project_root, file_path), 0)
+    }
+
+    /// [`Node::id_for`], unless that ID `is_taken` by another file: the path is then hashed
+    /// again with an increasing salt until the ID is free. Builders add files in path order,
+    /// so that the smaller of two colliding paths keeps the plain hash.
+    pub fn unique_id_for(
+        project_root: &Path,
+        file_path: &Path,
+        is_taken: impl Fn(NodeId) -> bool,
+    ) -> NodeId {
+        let key = relative_key(project_root, file_path);
+        (0..)
+            .map(|salt| hash_id(&key, salt))
+            .find(|id| !is_taken(*id))
+            .expect("a free ID")
+    }
+}
+
+fn relative_key(project_root: &Path, file_path: &Path) -> String {
+    let relative_path = file_path.strip_prefix(project_root).unwrap_or(file_path);
+    relative_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// FNV-1a of `key`, followed by a NUL byte and `salt` when it is not 0. Paths never contain
+/// NUL bytes, so salted keys cannot be mistaken for other paths.
+fn hash_id(key: &str, salt: u32) -> NodeId {
+    let salt = if salt == 0 {
+        String::new()
+    } else {
+        format!("\0{salt}")
+    };
+    let hash = key
+        .bytes()
+        .chain(salt.bytes())
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    hash & ((1 << 53) - 1)
+}
+
+/// A source node of `file_path`, labeled with its file name, for tests that do not touch the
+/// file system.
+#[cfg(test)]
+pub(crate) fn test_node(id: NodeId, file_path: impl Into<PathBuf>) -> Node {
+    let file_path = file_path.into();
+    Node {
+        id,
+        label: file_path
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path,
+        position: (OrderedFloat(0.0), OrderedFloat(0.0)),
+        fingerprint: FileFingerprint::default(),
+        dead: false,
+        kind: NodeKind::Source,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EdgeKind {
+    /// `import x from './x'`
+    Static,
+    /// `import('./x')`
+    Dynamic,
+    /// `require('./x')`
+    Require,
+    /// `export * from './x'` or `export { x } from './x'`
+    ReExport,
+    /// `import type { X } from './x'` or `export type { X } from './x'`
+    TypeOnly,
+    /// `import './x'`
+    SideEffect,
+    /// `import Worker from './x?worker'` or `'./x?sharedworker'`
+    Worker,
+    /// `import x from './x?raw'`, `'./x?url'` or `'./x?inline'`
+    Asset,
+}
+
+impl EdgeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::Static => "static",
+            EdgeKind::Dynamic => "dynamic",
+            EdgeKind::Require => "require",
+            EdgeKind::ReExport => "re-export",
+            EdgeKind::TypeOnly => "type-only",
+            EdgeKind::SideEffect => "side-effect",
+            EdgeKind::Worker => "worker",
+            EdgeKind::Asset => "asset",
+        }
+    }
+}
+
+/// Location of an import in the importing file. `start` and `end` are byte offsets,
+/// `line` and `column` are 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub start: u32,
+    pub end: u32,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Edge {
     pub from: NodeId,
     pub to: NodeId,
+    pub kind: EdgeKind,
+    /// Names imported or re-exported through this edge, as exported by `to`.
+    /// `default` for default imports and `*` for namespace imports and `export *`.
+    pub symbols: Vec<String>,
+    pub location: Option<SourceLocation>,
+    /// Query string or fragment of the import specifier, with its leading `?` or `#`, e.g.
+    /// `?vue&type=script` for `import x from './x.vue?vue&type=script'`.
+    #[serde(default)]
+    pub suffix: Option<String>,
 }
 
 impl Edge {
-    pub fn new(from: NodeId, to: NodeId) -> Self {
-        Self { from, to }
+    pub fn new(from: NodeId, to: NodeId, kind: EdgeKind) -> Self {
+        Self {
+            from,
+            to,
+            kind,
+            symbols: Vec::new(),
+            location: None,
+            suffix: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Node;
+    use std::path::Path;
+    use test_log::test;
+
+    #[test]
+    fn it_derives_stable_ids_from_paths() {
+        let root = Path::new("/project");
+        let id = Node::id_for(root, &root.join("src/index.ts"));
+        assert_eq!(id, 6936951944365909);
+        assert_eq!(Node::id_for(root, Path::new("src/index.ts")), id);
+        assert_eq!(
+            Node::id_for(Path::new("/other"), Path::new("/other/src/index.ts")),
+            id
+        );
+
+        assert_eq!(
+            Node::unique_id_for(root, &root.join("src/index.ts"), |_| false),
+            id
+        );
+        let salted = Node::unique_id_for(root, &root.join("src/index.ts"), |taken| taken == id);
+        assert_eq!(salted, 1602831120936634);
+        assert_ne!(salted, id);
     }
 }
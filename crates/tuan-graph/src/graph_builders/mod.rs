@@ -2,4 +2,6 @@ mod graph_builder;
 mod typescript;
 
 pub use graph_builder::GraphBuilder;
-pub use typescript::Typescript;
+pub use typescript::{
+    DEFAULT_EXCLUDE, DEFAULT_TEST_PATTERNS, Impact, Typescript, TypescriptOptions,
+};
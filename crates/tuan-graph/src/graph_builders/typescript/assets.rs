@@ -0,0 +1,219 @@
+use super::Typescript;
+use crate::graph::{Node, NodeId, NodeKind};
+use std::{collections::HashSet, path::PathBuf};
+
+impl Typescript {
+    /// Adds a node for every asset imported by `paths` that is not part of the graph yet, when
+    /// [`TypescriptOptions::assets`](super::TypescriptOptions) is set.
+    pub(super) fn add_assets(&mut self, paths: &[PathBuf]) -> Vec<Node> {
+        if !self.options.assets {
+            return Vec::new();
+        }
+
+        let asset_paths = paths
+            .iter()
+            .filter_map(|path| self.imports.get(path))
+            .flat_map(|imports| &imports.resolved)
+            .map(|import| &import.path)
+            .filter(|path| !self.files.contains_key(*path) && !self.assets.contains_key(*path))
+            .filter(|path| self.extractor.is_asset(path))
+            .cloned()
+            .collect::<HashSet<_>>();
+        // Sorted, so that colliding paths get the same IDs on every build.
+        let mut asset_paths = asset_paths.into_iter().collect::<Vec<_>>();
+        asset_paths.sort_unstable();
+
+        let mut added = Vec::new();
+        for path in asset_paths {
+            let Some(mut node) = Node::from_path(self.project_root(), path.clone()) else {
+                continue;
+            };
+            node.kind = NodeKind::Asset;
+            self.resolve_id_collision(&mut node);
+            self.graph.add_node(node.clone());
+            self.assets.insert(path, node.clone());
+            added.push(node);
+        }
+        added
+    }
+
+    /// Removes the assets nothing imports anymore.
+    pub(super) fn remove_unused_assets(&mut self) -> Vec<Node> {
+        let imported = self
+            .graph
+            .iter_edges()
+            .map(|edge| edge.to)
+            .collect::<HashSet<NodeId>>();
+        let unused = self
+            .assets
+            .iter()
+            .filter(|(_, node)| !imported.contains(&node.id))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+
+        unused
+            .into_iter()
+            .filter_map(|path| {
+                let node = self.assets.remove(&path)?;
+                self.graph.remove_node(node.id);
+                Some(node)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{EdgeKind, NodeKind};
+    use crate::graph_builders::{GraphBuilder, Typescript, TypescriptOptions};
+    use test_log::test;
+
+    #[test]
+    fn it_adds_stylesheet_and_asset_nodes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "src/App.tsx",
+            "import './App.scss';\nimport logo from './logo.svg';\nimport data from './data.json';\n",
+        );
+        write(
+            "src/App.scss",
+            "@use 'theme';\n@import '~pkg/reset.css';\n.logo { background: url(./logo.svg); }\n",
+        );
+        write(
+            "src/_theme.scss",
+            "@font-face { src: url('fonts/Inter.woff2?v=4') format('woff2'); }\n",
+        );
+        write("src/logo.svg", "<svg />");
+        write("src/data.json", "{}");
+        write("src/fonts/Inter.woff2", "");
+        write("node_modules/pkg/package.json", r#"{ "name": "pkg" }"#);
+        write("node_modules/pkg/reset.css", "");
+        write("src/unused.css", "");
+
+        let graph = Typescript::new(root.clone()).get_graph();
+        assert_eq!(graph.iter_nodes().count(), 1);
+
+        let options = TypescriptOptions {
+            assets: true,
+            ..Default::default()
+        };
+        let mut typescript = Typescript::with_options(root.clone(), options);
+        let graph = typescript.get_graph();
+        let mut nodes = graph
+            .iter_nodes()
+            .map(|node| (node.label.as_str(), node.kind))
+            .collect::<Vec<_>>();
+        nodes.sort_unstable_by_key(|(label, _)| *label);
+        assert_eq!(
+            nodes,
+            vec![
+                ("App.scss", NodeKind::Stylesheet),
+                ("App.tsx", NodeKind::Source),
+                ("Inter.woff2", NodeKind::Asset),
+                ("_theme.scss", NodeKind::Stylesheet),
+                ("data.json", NodeKind::Asset),
+                ("logo.svg", NodeKind::Asset),
+                ("unused.css", NodeKind::Stylesheet),
+            ]
+        );
+
+        let mut edges = graph
+            .iter_edges()
+            .map(|edge| {
+                (
+                    graph.nodes[&edge.from].label.as_str(),
+                    graph.nodes[&edge.to].label.as_str(),
+                    edge.kind,
+                )
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable_by_key(|(from, to, _)| (*from, *to));
+        assert_eq!(
+            edges,
+            vec![
+                ("App.scss", "_theme.scss", EdgeKind::Static),
+                ("App.scss", "logo.svg", EdgeKind::Asset),
+                ("App.tsx", "App.scss", EdgeKind::SideEffect),
+                ("App.tsx", "data.json", EdgeKind::Static),
+                ("App.tsx", "logo.svg", EdgeKind::Static),
+                ("_theme.scss", "Inter.woff2", EdgeKind::Asset),
+            ]
+        );
+        assert!(typescript.diagnostics().is_empty());
+
+        std::fs::remove_file(root.join("src/data.json")).unwrap();
+        let diff = typescript.update(vec![root.join("src/data.json")]);
+        assert_eq!(diff.removed_nodes.len(), 1);
+        assert_eq!(diff.removed_nodes[0].label, "data.json");
+        assert_eq!(diff.removed_edges.len(), 1);
+    }
+
+    #[test]
+    fn it_resolves_imports_of_assets_created_later() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("index.ts"), "import logo from './logo.svg';\n").unwrap();
+
+        let options = TypescriptOptions {
+            assets: true,
+            ..Default::default()
+        };
+        let mut typescript = Typescript::with_options(root.clone(), options);
+        let graph = typescript.get_graph();
+        assert_eq!(graph.iter_nodes().count(), 1);
+        assert_eq!(typescript.diagnostics().len(), 1);
+
+        std::fs::write(root.join("logo.svg"), "<svg />").unwrap();
+        let diff = typescript.update(vec![root.join("logo.svg")]);
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_nodes[0].label, "logo.svg");
+        assert_eq!(diff.added_nodes[0].kind, NodeKind::Asset);
+        assert_eq!(diff.added_edges.len(), 1);
+        assert!(typescript.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn it_adds_references_of_component_styles() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("Logo.vue"),
+            "<template><img /></template>\n<style lang=\"scss\">\n@use 'theme';\n.logo { background: url(./logo.svg); }\n</style>\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("_theme.scss"), "").unwrap();
+        std::fs::write(root.join("logo.svg"), "<svg />").unwrap();
+
+        let options = TypescriptOptions {
+            assets: true,
+            ..Default::default()
+        };
+        let typescript = Typescript::with_options(root.clone(), options);
+        let graph = typescript.get_graph();
+        let mut edges = graph
+            .iter_edges()
+            .map(|edge| {
+                (
+                    graph.nodes[&edge.from].label.as_str(),
+                    graph.nodes[&edge.to].label.as_str(),
+                    edge.kind,
+                    edge.location.as_ref().map(|location| location.line),
+                )
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable_by_key(|(_, to, _, _)| *to);
+        assert_eq!(
+            edges,
+            vec![
+                ("Logo.vue", "_theme.scss", EdgeKind::Static, Some(3)),
+                ("Logo.vue", "logo.svg", EdgeKind::Asset, Some(4)),
+            ]
+        );
+    }
+}
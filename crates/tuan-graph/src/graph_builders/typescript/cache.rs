@@ -0,0 +1,206 @@
+use super::{Typescript, TypescriptOptions, extractor::ExtractedImports};
+use crate::graph::FileFingerprint;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+/// Bump whenever the cache layout or the way imports are extracted changes.
+const CACHE_VERSION: u32 = 7;
+const CACHE_FILE_NAME: &str = "typescript-graph.json";
+
+#[derive(Serialize, Deserialize)]
+struct Cache {
+    version: u32,
+    options: TypescriptOptions,
+    configs: HashMap<PathBuf, FileFingerprint>,
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    fingerprint: FileFingerprint,
+    imports: ExtractedImports,
+}
+
+impl Typescript {
+    /// Reuses the cached imports of every file whose fingerprint did not change, unless they
+    /// may resolve differently because of files created or deleted since the cache was written.
+    /// Files with unresolved imports, or with imports of files that are gone, are always
+    /// extracted again, as packages may have been installed or removed in `node_modules`. The
+    /// whole cache is dropped when the options, a `package.json` or a `tsconfig.json` changed.
+    pub(super) fn load_cache(&mut self) {
+        let Some(cache_dir) = &self.cache_dir else {
+            return;
+        };
+        measure_time::info_time!("Loading graph cache");
+
+        let cache = match read_cache(&cache_dir.join(CACHE_FILE_NAME)) {
+            Ok(cache) => cache,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable graph cache: {}", e);
+                return;
+            }
+        };
+        if cache.version != CACHE_VERSION
+            || cache.options != self.options
+            || cache.configs != self.extractor.find_resolution_configs()
+        {
+            tracing::info!("Ignoring outdated graph cache");
+            return;
+        }
+
+        let created = self
+            .files
+            .keys()
+            .filter(|path| !cache.files.contains_key(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        let deleted = cache
+            .files
+            .keys()
+            .filter(|path| !self.files.contains_key(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for (path, cached) in cache.files {
+            if self
+                .files
+                .get(&path)
+                .is_some_and(|node| node.fingerprint == cached.fingerprint)
+                && !cached.imports.has_unresolved_imports()
+                && cached
+                    .imports
+                    .resolved
+                    .iter()
+                    .all(|import| import.path.exists())
+            {
+                self.imports.insert(path, cached.imports);
+            }
+        }
+        for path in self.possibly_affected_files(&created, &deleted) {
+            self.imports.remove(&path);
+        }
+
+        tracing::info!(
+            "Reusing cached imports for {} of {} files",
+            self.imports.len(),
+            self.files.len()
+        );
+    }
+
+    /// Writes the imports of every file to the cache directory given to
+    /// [`Typescript::with_cache`]. Files that could not be read are left out, so that they are
+    /// read again next time. Does nothing for builders created without a cache.
+    pub fn save_cache(&self) -> io::Result<()> {
+        let Some(cache_dir) = &self.cache_dir else {
+            return Ok(());
+        };
+        measure_time::info_time!("Saving graph cache");
+
+        let cache = Cache {
+            version: CACHE_VERSION,
+            options: self.options.clone(),
+            configs: self.extractor.find_resolution_configs(),
+            files: self
+                .imports
+                .iter()
+                .filter(|(_, imports)| !imports.is_unreadable())
+                .filter_map(|(path, imports)| {
+                    let node = self.files.get(path)?;
+                    Some((
+                        path.clone(),
+                        CachedFile {
+                            fingerprint: node.fingerprint.clone(),
+                            imports: imports.clone(),
+                        },
+                    ))
+                })
+                .collect(),
+        };
+
+        std::fs::create_dir_all(cache_dir)?;
+        let temp_path = cache_dir.join(format!("{CACHE_FILE_NAME}.tmp"));
+        serde_json::to_writer(BufWriter::new(File::create(&temp_path)?), &cache)?;
+        std::fs::rename(temp_path, cache_dir.join(CACHE_FILE_NAME))
+    }
+}
+
+fn read_cache(path: &Path) -> io::Result<Cache> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_builders::{GraphBuilder, Typescript};
+    use test_log::test;
+
+    #[test]
+    fn it_reuses_the_cache_and_detects_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let cache_dir = root.join(".tuan");
+        std::fs::write(root.join("a.ts"), "import { b } from './b';\n").unwrap();
+        std::fs::write(root.join("b.ts"), "export const b = 1;\n").unwrap();
+        std::fs::write(root.join("c.ts"), "import { d } from './d';\n").unwrap();
+
+        let graph =
+            Typescript::with_cache(root.clone(), cache_dir.clone(), Default::default()).get_graph();
+        assert_eq!(graph.iter_edges().count(), 1);
+        assert!(cache_dir.join(super::CACHE_FILE_NAME).exists());
+
+        std::fs::write(root.join("d.ts"), "export const d = 1;\n").unwrap();
+        let cached =
+            Typescript::with_cache(root.clone(), cache_dir.clone(), Default::default()).get_graph();
+        let uncached = Typescript::new(root.clone()).get_graph();
+        assert_eq!(cached.iter_nodes().count(), 4);
+        assert_eq!(cached.edges, uncached.edges);
+    }
+
+    #[test]
+    fn it_resolves_cached_imports_again_when_packages_change() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let cache_dir = root.join(".tuan");
+        let package = root.join("node_modules/pkg");
+        std::fs::write(root.join("a.ts"), "import 'pkg';\n").unwrap();
+
+        let typescript =
+            Typescript::with_cache(root.clone(), cache_dir.clone(), Default::default());
+        assert_eq!(typescript.diagnostics().len(), 1);
+
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(package.join("package.json"), r#"{ "name": "pkg" }"#).unwrap();
+        std::fs::write(package.join("index.js"), "").unwrap();
+        let typescript =
+            Typescript::with_cache(root.clone(), cache_dir.clone(), Default::default());
+        assert!(typescript.diagnostics().is_empty());
+
+        std::fs::remove_dir_all(&package).unwrap();
+        let typescript =
+            Typescript::with_cache(root.clone(), cache_dir.clone(), Default::default());
+        assert_eq!(typescript.diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn it_does_not_cache_unreadable_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let cache_dir = root.join(".tuan");
+        std::fs::write(root.join("a.ts"), "export const a = 1;\n").unwrap();
+        std::fs::write(root.join("b.ts"), b"\xff\xfe").unwrap();
+
+        let typescript =
+            Typescript::with_cache(root.clone(), cache_dir.clone(), Default::default());
+        assert_eq!(typescript.diagnostics().len(), 1);
+
+        let cache = super::read_cache(&cache_dir.join(super::CACHE_FILE_NAME)).unwrap();
+        assert!(cache.files.contains_key(&root.join("a.ts")));
+        assert!(!cache.files.contains_key(&root.join("b.ts")));
+    }
+}
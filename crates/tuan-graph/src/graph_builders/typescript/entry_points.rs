@@ -0,0 +1,234 @@
+use super::Typescript;
+use crate::graph::NodeId;
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+use tuan_walker::MODULE_EXTENSIONS;
+
+const OUTPUT_DIRS: [&str; 4] = ["dist", "build", "lib", "out"];
+
+impl Typescript {
+    /// Files the project is started from: the `main`, `module`, `exports` and `bin` targets of
+    /// every `package.json`, the scripts of every `index.html`, and the files whose
+    /// project-relative path matches one of `globs`.
+    pub fn entry_points(&self, globs: &[String]) -> Result<Vec<NodeId>, globset::Error> {
+        let targets = self
+            .extractor
+            .find_files_named("package.json")
+            .iter()
+            .flat_map(|manifest| package_entry_points(manifest))
+            .chain(
+                self.extractor
+                    .find_files_named("index.html")
+                    .iter()
+                    .flat_map(|html| html_entry_points(html)),
+            )
+            .collect::<Vec<_>>();
+
+        let mut entry_points = targets
+            .iter()
+            .filter_map(|target| {
+                let entry_point = self.resolve_entry_point(target);
+                if entry_point.is_none() {
+                    tracing::debug!("Entry point {} is not a source file", target.display());
+                }
+                entry_point
+            })
+            .collect::<HashSet<_>>();
+        entry_points.extend(self.files_matching(globs)?.map(|node| node.id));
+
+        let mut entry_points = entry_points.into_iter().collect::<Vec<_>>();
+        entry_points.sort_unstable();
+        Ok(entry_points)
+    }
+
+    /// Flags every file that [`Typescript::entry_points`] do not lead to, and returns them.
+    pub fn mark_dead_files(&mut self, globs: &[String]) -> Result<Vec<NodeId>, globset::Error> {
+        let entry_points = self.entry_points(globs)?;
+        Ok(self.graph.mark_unreachable(&entry_points))
+    }
+
+    /// Maps an entry point to a discovered file, trying the sources of a compiled file
+    /// (`index.js` -> `index.ts`) and directory indexes, with every module extension.
+    fn resolve_entry_point(&self, target: &Path) -> Option<NodeId> {
+        std::iter::once(target.to_path_buf())
+            .chain(
+                MODULE_EXTENSIONS
+                    .iter()
+                    .map(|extension| target.with_extension(extension)),
+            )
+            .chain(
+                MODULE_EXTENSIONS
+                    .iter()
+                    .map(|extension| target.join(format!("index.{extension}"))),
+            )
+            .filter_map(|candidate| candidate.canonicalize().ok())
+            .find_map(|candidate| self.files.get(&candidate))
+            .map(|node| node.id)
+    }
+}
+
+fn package_entry_points(manifest: &Path) -> Vec<PathBuf> {
+    let Some(package_dir) = manifest.parent() else {
+        return Vec::new();
+    };
+    let package = match std::fs::read_to_string(manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str::<Value>(&content).map_err(|e| e.to_string()))
+    {
+        Ok(package) => package,
+        Err(e) => {
+            tracing::warn!("Skipping entry points of {}: {}", manifest.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut targets = Vec::new();
+    for field in ["main", "module", "bin", "exports"] {
+        if let Some(value) = package.get(field) {
+            collect_strings(value, &mut targets);
+        }
+    }
+    targets
+        .into_iter()
+        // Subpath patterns like `./features/*.js` are matched against files by nobody.
+        .filter(|target| !target.contains('*'))
+        .flat_map(|target| {
+            let target = Path::new(&target);
+            let relative_target = target.strip_prefix("./").unwrap_or(target);
+            // `dist/index.js` is usually compiled from `src/index.ts`.
+            let source = OUTPUT_DIRS.iter().find_map(|dir| {
+                let rest = relative_target.strip_prefix(dir).ok()?;
+                Some(package_dir.join("src").join(rest))
+            });
+            std::iter::once(package_dir.join(target)).chain(source)
+        })
+        .collect()
+}
+
+/// String leaves of `bin` maps and of nested `exports` conditions.
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(string) => strings.push(string.clone()),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_strings(value, strings)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+/// Local `src` of the `<script>` tags. Absolute sources are relative to the directory of the
+/// page, which is how Vite serves them.
+fn html_entry_points(html: &Path) -> Vec<PathBuf> {
+    let (Some(page_dir), Ok(content)) = (html.parent(), std::fs::read_to_string(html)) else {
+        return Vec::new();
+    };
+    let lowercase = content.to_ascii_lowercase();
+
+    let mut targets = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = lowercase[offset..].find("<script") {
+        let tag_start = offset + start;
+        let Some(tag_len) = lowercase[tag_start..].find('>') else {
+            break;
+        };
+        let tag = &content[tag_start..tag_start + tag_len];
+        offset = tag_start + tag_len;
+
+        if let Some(src) = attribute_value(tag, "src")
+            && !src.is_empty()
+            && !src.contains("://")
+            && !src.starts_with("//")
+        {
+            targets.push(page_dir.join(src.trim_start_matches('/')));
+        }
+    }
+    targets
+}
+
+/// Value of the attribute `name` of an HTML `tag`, which has to be a whole attribute name:
+/// `src` does not match `data-src`.
+fn attribute_value<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lowercase = tag.to_ascii_lowercase();
+    lowercase.match_indices(name).find_map(|(start, _)| {
+        if !tag[..start].ends_with(char::is_whitespace) {
+            return None;
+        }
+        let value = tag[start + name.len()..].trim_start().strip_prefix('=')?;
+        let value = value.trim_start();
+        match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next(),
+            _ => value.split_whitespace().next(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_builders::{GraphBuilder, Typescript};
+    use test_log::test;
+
+    #[test]
+    fn it_marks_files_unreachable_from_entry_points() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "package.json",
+            r#"{ "main": "dist/index.js", "bin": { "tool": "./src/cli.ts" }, "exports": { "./feature": { "import": "./src/feature" } } }"#,
+        );
+        write(
+            "index.html",
+            r#"<script type="module" src="/src/web.ts"></script>"#,
+        );
+        write("src/index.ts", "import './used';\n");
+        write("src/used.ts", "");
+        write("src/cli.ts", "");
+        write("src/feature/index.ts", "");
+        write("src/web.ts", "");
+        write("scripts/release.ts", "");
+        write("src/orphan.ts", "import './orphan-dep';\n");
+        write("src/orphan-dep.ts", "");
+
+        let mut typescript = Typescript::new(root.clone());
+        let dead = typescript
+            .mark_dead_files(&["scripts/**".to_string()])
+            .unwrap();
+
+        let id = |path: &str| crate::graph::Node::id_for(&root, Path::new(&root.join(path)));
+        let mut expected = vec![id("src/orphan.ts"), id("src/orphan-dep.ts")];
+        expected.sort_unstable();
+        assert_eq!(dead, expected);
+
+        let graph = typescript.get_graph();
+        assert!(graph.nodes[&id("src/orphan.ts")].dead);
+        assert!(!graph.nodes[&id("src/used.ts")].dead);
+    }
+
+    #[test]
+    fn it_reads_whole_src_attributes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let html = temp_dir.path().join("index.html");
+        std::fs::write(
+            &html,
+            r#"<script data-src="/src/lazy.ts" type="module" src = '/src/web.ts'></script>
+<script data-src="/src/other.ts"></script>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            html_entry_points(&html),
+            vec![temp_dir.path().join("src/web.ts")]
+        );
+    }
+}
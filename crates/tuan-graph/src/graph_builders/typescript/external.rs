@@ -0,0 +1,181 @@
+use super::Typescript;
+use crate::graph::{FileFingerprint, Node, NodeId, NodeKind};
+use ordered_float::OrderedFloat;
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+impl Typescript {
+    /// Adds a node for every package imported by `paths` that is not part of the graph yet,
+    /// when [`TypescriptOptions::external_packages`](super::TypescriptOptions) is set.
+    pub(super) fn add_external_packages(&mut self, paths: &[PathBuf]) -> Vec<Node> {
+        if !self.options.external_packages {
+            return Vec::new();
+        }
+
+        let roots = paths
+            .iter()
+            .filter_map(|path| self.imports.get(path))
+            .flat_map(|imports| &imports.resolved)
+            .filter(|import| !self.files.contains_key(&import.path))
+            .filter_map(|import| package_root(&import.path))
+            .filter(|root| !self.externals.contains_key(root))
+            .collect::<HashSet<_>>();
+        // Sorted, so that colliding paths get the same IDs on every build.
+        let mut roots = roots.into_iter().collect::<Vec<_>>();
+        roots.sort_unstable();
+
+        let mut added = Vec::new();
+        for root in roots {
+            let Some(mut node) = external_package_node(self.project_root(), &root) else {
+                tracing::debug!("No package.json in {}", root.display());
+                continue;
+            };
+            self.resolve_id_collision(&mut node);
+            self.graph.add_node(node.clone());
+            self.externals.insert(root, node.clone());
+            added.push(node);
+        }
+        added
+    }
+
+    /// Removes the packages no file imports anymore.
+    pub(super) fn remove_unused_external_packages(&mut self) -> Vec<Node> {
+        let imported = self
+            .graph
+            .iter_edges()
+            .map(|edge| edge.to)
+            .collect::<HashSet<NodeId>>();
+        let unused = self
+            .externals
+            .iter()
+            .filter(|(_, node)| !imported.contains(&node.id))
+            .map(|(root, _)| root.clone())
+            .collect::<Vec<_>>();
+
+        unused
+            .into_iter()
+            .filter_map(|root| {
+                let node = self.externals.remove(&root)?;
+                self.graph.remove_node(node.id);
+                Some(node)
+            })
+            .collect()
+    }
+
+    /// Package node of a file resolved into `node_modules`.
+    pub(super) fn external_package(&self, path: &Path) -> Option<&Node> {
+        if self.externals.is_empty() {
+            return None;
+        }
+        self.externals.get(&package_root(path)?)
+    }
+}
+
+/// `.../node_modules/react` for `.../node_modules/react/index.js`, and
+/// `.../node_modules/@scope/name` for scoped packages. The innermost `node_modules` wins, so
+/// that pnpm's `.pnpm/react@18.2.0/node_modules/react` maps to the real package.
+fn package_root(path: &Path) -> Option<PathBuf> {
+    let components = path.components().collect::<Vec<_>>();
+    let node_modules_idx = components
+        .iter()
+        .rposition(|component| component.as_os_str() == "node_modules")?;
+    let name_len = if components
+        .get(node_modules_idx + 1)?
+        .as_os_str()
+        .to_string_lossy()
+        .starts_with('@')
+    {
+        2
+    } else {
+        1
+    };
+    let root_len = node_modules_idx + 1 + name_len;
+    (components.len() > root_len).then(|| components[..root_len].iter().collect())
+}
+
+/// Packages are identified by name and version, so that two versions of the same package
+/// get two nodes.
+fn external_package_node(project_root: &Path, root: &Path) -> Option<Node> {
+    let manifest = root.join("package.json");
+    let package = serde_json::from_str::<Value>(&std::fs::read_to_string(&manifest).ok()?).ok()?;
+    let name = package
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| Some(root.file_name()?.to_string_lossy().to_string()))?;
+    let label = match package.get("version").and_then(Value::as_str) {
+        Some(version) => format!("{name}@{version}"),
+        None => name,
+    };
+
+    Some(Node {
+        id: Node::id_for(project_root, &Path::new("node_modules").join(&label)),
+        label,
+        file_path: root.to_path_buf(),
+        position: (OrderedFloat(0.0), OrderedFloat(0.0)),
+        fingerprint: FileFingerprint::from_path(&manifest)?,
+        dead: false,
+        kind: NodeKind::ExternalPackage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::NodeKind;
+    use crate::graph_builders::{GraphBuilder, Typescript, TypescriptOptions};
+    use test_log::test;
+
+    #[test]
+    fn it_adds_a_node_per_external_package() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "node_modules/react/package.json",
+            r#"{ "name": "react", "version": "18.2.0", "main": "index.js" }"#,
+        );
+        write("node_modules/react/index.js", "");
+        write(
+            "node_modules/@scope/pkg/package.json",
+            r#"{ "name": "@scope/pkg", "version": "1.0.0", "main": "dist/index.js" }"#,
+        );
+        write("node_modules/@scope/pkg/dist/index.js", "");
+        write(
+            "a.ts",
+            "import React from 'react';\nimport { x } from '@scope/pkg';\n",
+        );
+        write("b.ts", "import { useState } from 'react';\n");
+
+        let graph = Typescript::new(root.clone()).get_graph();
+        assert_eq!(graph.iter_nodes().count(), 2);
+        assert_eq!(graph.iter_edges().count(), 0);
+
+        let options = TypescriptOptions {
+            external_packages: true,
+            ..Default::default()
+        };
+        let mut typescript = Typescript::with_options(root.clone(), options);
+        let graph = typescript.get_graph();
+        let mut labels = graph
+            .iter_nodes()
+            .filter(|node| node.kind == NodeKind::ExternalPackage)
+            .map(|node| node.label.as_str())
+            .collect::<Vec<_>>();
+        labels.sort_unstable();
+        assert_eq!(labels, vec!["@scope/pkg@1.0.0", "react@18.2.0"]);
+        assert_eq!(graph.iter_edges().count(), 3);
+
+        std::fs::write(root.join("a.ts"), "import React from 'react';\n").unwrap();
+        let diff = typescript.update(vec![root.join("a.ts")]);
+        assert_eq!(diff.removed_nodes.len(), 1);
+        assert_eq!(diff.removed_nodes[0].label, "@scope/pkg@1.0.0");
+        assert_eq!(diff.removed_edges.len(), 1);
+    }
+}
@@ -1,26 +1,115 @@
-use crate::{graph::{FileFingerprint, Node}, graph_builders::typescript::visitor::Visitor};
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticKind, Severity},
+    graph::{FileFingerprint, Node, NodeKind},
+    graph_builders::typescript::{
+        TypescriptOptions, stylesheet,
+        visitor::{Import, Visitor, split_suffix},
+    },
+};
 use oxc_ast_visit::Visit;
-use oxc_parser::{Parser, ParserReturn};
+use oxc_parser::{ParseOptions, Parser, ParserReturn};
 use oxc_resolver::{ResolveOptions, Resolver};
-use oxc_span::SourceType;
+use oxc_span::{SourceType, Span};
+use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
     collections::HashMap,
     path::{Path, PathBuf},
 };
-use walkdir::WalkDir;
+use tuan_walker::{DirEntry, MODULE_EXTENSIONS, SOURCE_EXTENSIONS, SourceFilter};
 
 pub(super) struct Extractor {
+    pub(super) project_root: PathBuf,
     resolver: Resolver,
+    filter: SourceFilter,
+    extra_extensions: Vec<String>,
+    max_file_size: Option<u64>,
+    follow_symlinks: bool,
+    assets: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct ExtractedImports {
+    pub(super) resolved: Vec<Import>,
+    /// Unresolved imports and parse errors.
+    pub(super) diagnostics: Vec<Diagnostic>,
+}
+
+impl ExtractedImports {
+    /// Nothing could be extracted because the file could not be read.
+    pub(super) fn unreadable(node: &Node, error: &dyn std::error::Error) -> Self {
+        Self {
+            resolved: Vec::new(),
+            diagnostics: vec![Diagnostic {
+                kind: DiagnosticKind::ReadError,
+                severity: Severity::Error,
+                file_path: node.file_path.clone(),
+                location: None,
+                specifier: None,
+                message: error.to_string(),
+            }],
+        }
+    }
+
+    /// Whether the file could not be read, which may be temporary.
+    pub(super) fn is_unreadable(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.kind == DiagnosticKind::ReadError)
+    }
+
+    pub(super) fn has_unresolved_imports(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.kind == DiagnosticKind::UnresolvedImport)
+    }
 }
 
 impl Extractor {
-    pub(super) fn new(project_path: &PathBuf) -> Self {
+    pub(super) fn new(project_path: &PathBuf, options: &TypescriptOptions) -> Self {
+        let extra_extensions = options
+            .extensions
+            .iter()
+            .map(|extension| extension.trim_start_matches('.').to_string())
+            .filter(|extension| {
+                !SOURCE_EXTENSIONS.contains(&extension.as_str())
+                    && !tuan_sfc::COMPONENT_EXTENSIONS.contains(&extension.as_str())
+            })
+            .collect::<Vec<_>>();
+
+        let project_root = project_path
+            .canonicalize()
+            .unwrap_or_else(|_| project_path.clone());
+        let stylesheet_extensions = if options.assets {
+            &stylesheet::STYLESHEET_EXTENSIONS[..]
+        } else {
+            &[]
+        };
+        let filter = SourceFilter::new(
+            &project_root,
+            SOURCE_EXTENSIONS
+                .iter()
+                .chain(&tuan_sfc::COMPONENT_EXTENSIONS)
+                .chain(stylesheet_extensions)
+                .map(|extension| extension.to_string())
+                .chain(extra_extensions.iter().cloned()),
+        )
+        .include(&options.include)
+        .exclude(&options.exclude)
+        .include_declarations(options.include_declarations);
+
         Self {
-            resolver: Self::build_resolver(&project_path),
+            project_root,
+            resolver: Self::build_resolver(&project_path, &extra_extensions),
+            filter,
+            extra_extensions,
+            max_file_size: options.max_file_size,
+            follow_symlinks: options.follow_symlinks,
+            assets: options.assets,
         }
     }
 
-    fn build_resolver(project_root: &Path) -> Resolver {
+    fn build_resolver(project_root: &Path, extra_extensions: &[String]) -> Resolver {
         let config_file = project_root.join("tsconfig.json");
 
         let options = ResolveOptions {
@@ -35,7 +124,14 @@ impl Extractor {
                 ".cjs".into(),
                 ".json".into(),
                 ".d.ts".into(),
-            ],
+            ]
+            .into_iter()
+            .chain(
+                extra_extensions
+                    .iter()
+                    .map(|extension| format!(".{extension}")),
+            )
+            .collect(),
             condition_names: vec!["node".into(), "import".into(), "types".into()],
             main_fields: vec![
                 "types".into(),
@@ -44,38 +140,26 @@ impl Extractor {
                 "main".into(),
             ],
             tsconfig: Some(oxc_resolver::TsconfigDiscovery::Auto),
+            builtin_modules: true,
             ..ResolveOptions::default()
         };
 
         Resolver::new(options)
     }
 
-    pub(super) fn find_typescript_files(&self, root: &Path) -> HashMap<FileFingerprint, Node> {
-        WalkDir::new(root)
-            .into_iter()
-            .filter_entry(|entry| {
-                !entry.path().components().any(|component| {
-                    matches!(
-                        component.as_os_str().to_str(),
-                        Some("node_modules")
-                            | Some(".git")
-                            | Some("dist")
-                            | Some("build")
-                            | Some("coverage")
-                            | Some(".svelte-kit")
-                    )
-                })
-            })
-            .filter_map(|res| {
-                let e = res.ok()?;
-                if e.file_type().is_file() {
-                    match e.path().extension().and_then(|s| s.to_str()) {
-                        Some("ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs") => {
-                            let node = Node::from_path(e.into_path())?;
-                            Some((node.key.clone(), node))
-                        }
-                        _ => None,
-                    }
+    /// Files are keyed by their canonical path, which is also what the resolver returns for
+    /// imports. The node fingerprint is only used to detect changes.
+    pub(super) fn find_typescript_files(&self) -> HashMap<PathBuf, Node> {
+        self.find_typescript_files_in(&self.project_root)
+    }
+
+    pub(super) fn find_typescript_files_in(&self, dir: &Path) -> HashMap<PathBuf, Node> {
+        self.walk(dir)
+            .filter_map(|e| {
+                if is_file(&e) && self.is_typescript_file(e.path()) {
+                    let canonical_path = e.path().canonicalize().ok()?;
+                    let node = self.node(e.into_path())?;
+                    Some((canonical_path, node))
                 } else {
                     None
                 }
@@ -83,31 +167,251 @@ impl Extractor {
             .collect::<HashMap<_, _>>()
     }
 
+    /// `package.json`, `tsconfig*.json` and `jsconfig.json` files, which affect how imports
+    /// resolve.
+    pub(super) fn find_resolution_configs(&self) -> HashMap<PathBuf, FileFingerprint> {
+        self.walk(&self.project_root)
+            .filter_map(|e| {
+                let name = e.file_name().to_str()?;
+                let is_config = name == "package.json"
+                    || name == "jsconfig.json"
+                    || (name.starts_with("tsconfig") && name.ends_with(".json"));
+                if is_file(&e) && is_config {
+                    let fingerprint = FileFingerprint::from_path(e.path())?;
+                    Some((e.into_path(), fingerprint))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Files named `file_name` outside of excluded directories, e.g. every `package.json` of a
+    /// workspace.
+    pub(super) fn find_files_named(&self, file_name: &str) -> Vec<PathBuf> {
+        self.walk(&self.project_root)
+            .filter(|e| is_file(e) && e.file_name() == file_name)
+            .map(DirEntry::into_path)
+            .collect()
+    }
+
+    /// Walks `dir`, skipping excluded and ignored files, see [`SourceFilter::walk`].
+    fn walk(&self, dir: &Path) -> impl Iterator<Item = DirEntry> {
+        self.filter.walk(dir, self.follow_symlinks)
+    }
+
+    /// `dir` and every directory below it that discovery enters.
+    pub(super) fn find_directories_in(&self, dir: &Path) -> Vec<PathBuf> {
+        self.walk(dir)
+            .filter(|e| e.file_type().is_some_and(|file_type| file_type.is_dir()))
+            .map(DirEntry::into_path)
+            .collect()
+    }
+
+    /// Like [`Extractor::is_typescript_file`], but also reads the ignore files of every parent
+    /// directory.
+    pub(super) fn is_discoverable(&self, path: &Path) -> bool {
+        self.is_typescript_file(path) && !tuan_walker::is_ignored(&self.project_root, path)
+    }
+
+    /// Whether `path` would be picked up by [`Extractor::find_typescript_files`].
+    pub(super) fn is_typescript_file(&self, path: &Path) -> bool {
+        self.filter.is_source_file(path)
+            && self.max_file_size.is_none_or(|max_file_size| {
+                std::fs::metadata(path).is_ok_and(|metadata| metadata.len() <= max_file_size)
+            })
+    }
+
+    /// Node of a file picked up by [`Extractor::find_typescript_files`].
+    pub(super) fn node(&self, file_path: PathBuf) -> Option<Node> {
+        let mut node = Node::from_path(&self.project_root, file_path)?;
+        if stylesheet::is_stylesheet(&node.file_path) {
+            node.kind = NodeKind::Stylesheet;
+        }
+        Some(node)
+    }
+
+    /// Whether `path` is an imported non-code file, e.g. an image, a font or a JSON file, when
+    /// assets are enabled.
+    pub(super) fn is_asset(&self, path: &Path) -> bool {
+        let is_code = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|extension| {
+                MODULE_EXTENSIONS.contains(&extension)
+                    || tuan_sfc::COMPONENT_EXTENSIONS.contains(&extension)
+                    || stylesheet::STYLESHEET_EXTENSIONS.contains(&extension)
+                    || self.extra_extensions.iter().any(|extra| extra == extension)
+            });
+        self.assets
+            && !is_code
+            && path.starts_with(&self.project_root)
+            && !self.is_excluded(path)
+            && path.is_file()
+    }
+
+    pub(super) fn clear_resolver_cache(&self) {
+        self.resolver.clear_cache();
+    }
+
+    /// Whether `path` or one of its parent directories is excluded. Exclude globs are matched
+    /// against project-relative paths.
+    pub(super) fn is_excluded(&self, path: &Path) -> bool {
+        self.filter.is_excluded(path)
+    }
+
     pub(super) fn extract_typescript_imports(
         &self,
         node: &Node,
-    ) -> Result<Vec<Node>, Box<dyn std::error::Error>> {
+    ) -> Result<ExtractedImports, Box<dyn std::error::Error>> {
         let source_code = std::fs::read_to_string(&node.file_path)?;
+        if node.kind == NodeKind::Stylesheet {
+            return Ok(self.extract_stylesheet_imports(node, &source_code));
+        }
         let allocator = oxc_allocator::Allocator::default();
-        let source_type = match node.file_path.extension().and_then(|s| s.to_str()) {
-            Some("tsx") => SourceType::tsx(),
-            Some("ts") => SourceType::ts(),
-            Some("jsx") => SourceType::jsx(),
-            Some("js") => SourceType::unambiguous().with_jsx(true).with_module(true),
-            Some("mjs") => SourceType::mjs(),
-            Some("cjs") => SourceType::cjs(),
-            _ => SourceType::unambiguous(),
+        // Components are parsed as their scripts only, with the same offsets.
+        let (script_code, source_type) =
+            match tuan_sfc::script_source(&node.file_path, &source_code) {
+                Some((script_code, source_type)) => (Cow::Owned(script_code), source_type),
+                None => (
+                    Cow::Borrowed(source_code.as_str()),
+                    match node.file_path.extension().and_then(|s| s.to_str()) {
+                        Some("tsx") => SourceType::tsx(),
+                        Some("ts") => SourceType::ts(),
+                        Some("jsx") => SourceType::jsx(),
+                        Some("js") => SourceType::unambiguous().with_jsx(true).with_module(true),
+                        Some("mjs") => SourceType::mjs(),
+                        Some("cjs") => SourceType::cjs(),
+                        _ => SourceType::unambiguous(),
+                    },
+                ),
+            };
+
+        let options = ParseOptions {
+            // Astro frontmatter may return early, e.g. `return Astro.redirect('/')`.
+            allow_return_outside_function: tuan_sfc::is_component(&node.file_path),
+            ..ParseOptions::default()
         };
 
         let ParserReturn {
             program, errors, ..
-        } = Parser::new(&allocator, &source_code, source_type).parse();
-        for e in &errors {
-            eprintln!("Parse error in {}: {e}", node.file_path.display());
-        }
+        } = Parser::new(&allocator, &script_code, source_type)
+            .with_options(options)
+            .parse();
 
-        let mut visitor = Visitor::new(&node.file_path, &self.resolver);
+        let mut visitor = Visitor::new(&node.file_path, &source_code, &self.resolver);
+        let parse_errors = errors
+            .iter()
+            .map(|e| {
+                let span = e
+                    .labels
+                    .as_ref()
+                    .and_then(|labels| labels.first())
+                    .map(|label| {
+                        Span::new(label.offset() as u32, (label.offset() + label.len()) as u32)
+                    });
+                Diagnostic {
+                    kind: DiagnosticKind::ParseError,
+                    severity: Severity::Error,
+                    file_path: node.file_path.clone(),
+                    location: span.map(|span| visitor.location(span)),
+                    specifier: None,
+                    message: e.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        visitor.diagnostics.extend(parse_errors);
         visitor.visit_program(&program);
-        Ok(visitor.imports)
+        if self.assets {
+            // `<style>` blocks default to CSS, and resolve like a stylesheet next to the
+            // component.
+            for block in tuan_sfc::style_blocks(&node.file_path, &source_code) {
+                let extension = block.attribute("lang").unwrap_or("css");
+                self.add_stylesheet_references(&mut visitor, &node.file_path, &block, extension);
+            }
+        }
+        Ok(ExtractedImports {
+            resolved: visitor.imports,
+            diagnostics: visitor.diagnostics,
+        })
+    }
+
+    /// Stylesheet references resolve relative to the stylesheet first, like URLs, then as
+    /// packages. `~` marks a package, as with webpack's `css-loader`.
+    fn extract_stylesheet_imports(&self, node: &Node, source_code: &str) -> ExtractedImports {
+        let mut visitor = Visitor::new(&node.file_path, source_code, &self.resolver);
+        let extension = node
+            .file_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let block = tuan_sfc::Block {
+            content: source_code,
+            offset: 0,
+            attributes: Vec::new(),
+        };
+        self.add_stylesheet_references(&mut visitor, &node.file_path, &block, extension);
+
+        ExtractedImports {
+            resolved: visitor.imports,
+            diagnostics: visitor.diagnostics,
+        }
+    }
+
+    /// Resolves the references of the stylesheet `block` of `file_path`, written in the
+    /// language of `extension`.
+    fn add_stylesheet_references(
+        &self,
+        visitor: &mut Visitor<'_>,
+        file_path: &Path,
+        block: &tuan_sfc::Block<'_>,
+        extension: &str,
+    ) {
+        let dir = file_path.parent().unwrap_or(Path::new(""));
+        for reference in stylesheet::references(block.content) {
+            let span = Span::new(
+                reference.span.start + block.offset as u32,
+                reference.span.end + block.offset as u32,
+            );
+            let location = visitor.location(span);
+            let (request, suffix) = split_suffix(reference.specifier);
+            let local = if request.starts_with('~') {
+                None
+            } else {
+                stylesheet::candidates(dir, request, extension)
+                    .into_iter()
+                    .filter(|candidate| candidate.is_file())
+                    .find_map(|candidate| candidate.canonicalize().ok())
+            };
+            let resolution = match local {
+                Some(path) => Ok(path),
+                None => self
+                    .resolver
+                    .resolve(dir, request.trim_start_matches('~'))
+                    .map(|resolution| resolution.full_path().to_path_buf()),
+            };
+
+            match resolution {
+                Ok(path) => visitor.imports.push(Import {
+                    path,
+                    kind: reference.kind,
+                    symbols: Vec::new(),
+                    location,
+                    suffix: suffix.map(str::to_string),
+                }),
+                Err(e) => visitor.diagnostics.push(Diagnostic::unresolved_import(
+                    file_path.to_path_buf(),
+                    location,
+                    reference.specifier,
+                    e.to_string(),
+                )),
+            }
+        }
     }
 }
+
+fn is_file(entry: &DirEntry) -> bool {
+    entry
+        .file_type()
+        .is_some_and(|file_type| file_type.is_file())
+}
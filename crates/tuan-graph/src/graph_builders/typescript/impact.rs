@@ -0,0 +1,189 @@
+use super::{Typescript, incremental::canonicalize, visitor::split_suffix};
+use crate::{diagnostics::DiagnosticKind, graph::NodeId};
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+/// Test files recognized by [`Typescript::impact`] unless other patterns are given.
+pub const DEFAULT_TEST_PATTERNS: [&str; 3] = ["**/*.test.*", "**/*.spec.*", "**/__tests__/**"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Impact {
+    /// Changed paths that are part of the graph.
+    pub changed: Vec<NodeId>,
+    /// Changed files and every file importing them, directly or transitively, including the
+    /// importers of deleted files.
+    pub affected: Vec<NodeId>,
+    /// Affected files matching the test patterns.
+    pub tests: Vec<NodeId>,
+}
+
+impl Typescript {
+    /// Files affected by a change of `paths`, e.g. the output of `git diff --name-only`.
+    /// Relative paths are resolved against the project root. Files deleted since the graph was
+    /// built affect the files whose imports of them no longer resolve. Other paths that are
+    /// not part of the graph, like a `README.md`, are ignored.
+    pub fn impact<I, S>(&self, paths: I, test_patterns: &[S]) -> Result<Impact, globset::Error>
+    where
+        I: IntoIterator<Item = PathBuf>,
+        S: AsRef<str>,
+    {
+        let mut changed = Vec::new();
+        let mut importers = Vec::new();
+        for path in paths {
+            let path = canonicalize(&self.project_root().join(path));
+            match self.files.get(&path).or_else(|| self.assets.get(&path)) {
+                Some(node) => changed.push(node.id),
+                None if !path.exists() => importers.extend(self.importers_of_deleted(&path)),
+                None => {}
+            }
+        }
+        changed.sort_unstable();
+        changed.dedup();
+
+        let affected = self.graph.affected_by(&[&changed[..], &importers].concat());
+        let affected_ids = affected.iter().collect::<HashSet<_>>();
+        let mut tests = self
+            .files_matching(test_patterns)?
+            .map(|node| node.id)
+            .filter(|id| affected_ids.contains(id))
+            .collect::<Vec<_>>();
+        tests.sort_unstable();
+
+        Ok(Impact {
+            changed,
+            affected,
+            tests,
+        })
+    }
+
+    /// Files with an unresolved relative import of `path`, a file that is not part of the
+    /// graph anymore: `./foo`, `./foo.js` or `./foo.ts` for `foo.ts`, and `./foo` for
+    /// `foo/index.ts`.
+    fn importers_of_deleted(&self, path: &Path) -> Vec<NodeId> {
+        let stem = path.with_extension("");
+        let index_dir = path
+            .file_stem()
+            .is_some_and(|stem| stem == "index")
+            .then(|| path.parent())
+            .flatten();
+        let imports_path = |importer: &Path, specifier: &str| {
+            let Some(dir) = importer.parent() else {
+                return false;
+            };
+            let target = normalize(&dir.join(split_suffix(specifier).0));
+            target == path
+                || target == stem
+                || target.with_extension("") == stem
+                || index_dir.is_some_and(|index_dir| target == index_dir)
+        };
+
+        self.imports
+            .iter()
+            .filter(|(importer, imports)| {
+                imports.diagnostics.iter().any(|diagnostic| {
+                    diagnostic.kind == DiagnosticKind::UnresolvedImport
+                        && diagnostic.specifier.as_deref().is_some_and(|specifier| {
+                            specifier.starts_with('.') && imports_path(importer, specifier)
+                        })
+                })
+            })
+            .filter_map(|(importer, _)| self.files.get(importer))
+            .map(|node| node.id)
+            .collect()
+    }
+}
+
+/// Resolves `.` and `..` without touching the file system, as `path` may not exist.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DEFAULT_TEST_PATTERNS;
+    use crate::graph::Node;
+    use crate::graph_builders::Typescript;
+    use std::path::PathBuf;
+    use test_log::test;
+
+    #[test]
+    fn it_finds_affected_files_and_tests() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("src/util.ts", "");
+        write("src/service.ts", "import './util';\n");
+        write("src/other.ts", "");
+        write("src/service.test.ts", "import './service';\n");
+        write("src/__tests__/util.ts", "import '../util';\n");
+        write("src/other.spec.ts", "import './other';\n");
+
+        let typescript = Typescript::new(root.clone());
+        let id = |path: &str| Node::id_for(&root, &root.join(path));
+        let sorted = |mut ids: Vec<u64>| {
+            ids.sort_unstable();
+            ids
+        };
+
+        let impact = typescript
+            .impact(
+                vec![PathBuf::from("src/util.ts"), PathBuf::from("README.md")],
+                &DEFAULT_TEST_PATTERNS,
+            )
+            .unwrap();
+        assert_eq!(impact.changed, vec![id("src/util.ts")]);
+        assert_eq!(
+            impact.affected,
+            sorted(vec![
+                id("src/util.ts"),
+                id("src/service.ts"),
+                id("src/service.test.ts"),
+                id("src/__tests__/util.ts"),
+            ])
+        );
+        assert_eq!(
+            impact.tests,
+            sorted(vec![id("src/service.test.ts"), id("src/__tests__/util.ts")])
+        );
+
+        let impact = typescript
+            .impact(vec![root.join("src/util.ts")], &["**/__tests__/**"])
+            .unwrap();
+        assert_eq!(impact.tests, vec![id("src/__tests__/util.ts")]);
+
+        std::fs::remove_file(root.join("src/util.ts")).unwrap();
+        let typescript = Typescript::new(root.clone());
+        let impact = typescript
+            .impact(vec![PathBuf::from("src/util.ts")], &DEFAULT_TEST_PATTERNS)
+            .unwrap();
+        assert!(impact.changed.is_empty());
+        assert_eq!(
+            impact.affected,
+            sorted(vec![
+                id("src/service.ts"),
+                id("src/service.test.ts"),
+                id("src/__tests__/util.ts"),
+            ])
+        );
+        assert_eq!(
+            impact.tests,
+            sorted(vec![id("src/service.test.ts"), id("src/__tests__/util.ts")])
+        );
+    }
+}
@@ -0,0 +1,441 @@
+use super::Typescript;
+use crate::graph::{GraphDiff, Node};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+impl Typescript {
+    /// Patches the graph after the given paths were changed, created or deleted, and reports
+    /// what changed. Only the touched files and the files whose imports may now resolve
+    /// differently are parsed again. Files whose fingerprint did not change are skipped.
+    pub fn update<I>(&mut self, paths: I) -> GraphDiff
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        measure_time::info_time!("Updating TypeScript graph");
+
+        let mut config_changed = false;
+        let mut candidates = HashSet::new();
+        for path in paths {
+            config_changed |= is_resolution_config(&path);
+
+            let path = canonicalize(&path);
+            if path.is_dir() {
+                candidates.extend(self.extractor.find_typescript_files_in(&path).into_keys());
+            }
+            if path.is_file() {
+                candidates.insert(path.clone());
+            }
+            candidates.extend(
+                self.files
+                    .keys()
+                    .chain(self.assets.keys())
+                    .filter(|known| known.starts_with(&path) && !known.exists())
+                    .cloned(),
+            );
+        }
+
+        let mut created = Vec::new();
+        let mut changed = Vec::new();
+        let mut deleted = Vec::new();
+        // Assets only become nodes once imported, but may fix unresolved imports.
+        let mut created_assets = Vec::new();
+        for path in candidates {
+            let known = self.files.get(&path);
+            // Known files went through the ignore files when they were discovered.
+            let discoverable = match known {
+                Some(_) => self.extractor.is_typescript_file(&path),
+                None => self.extractor.is_discoverable(&path),
+            };
+            let node = if path.is_file() && discoverable {
+                let file_path = known.map_or_else(|| path.clone(), |node| node.file_path.clone());
+                self.extractor.node(file_path)
+            } else {
+                None
+            };
+
+            match (known, node) {
+                (None, Some(node)) => created.push((path, node)),
+                (Some(known), Some(mut node)) if known.fingerprint != node.fingerprint => {
+                    node.id = known.id;
+                    node.position = known.position;
+                    changed.push((path, node));
+                }
+                (Some(_), None) => deleted.push(path),
+                (None, None) if self.assets.contains_key(&path) && !path.exists() => {
+                    deleted.push(path)
+                }
+                (None, None)
+                    if !self.assets.contains_key(&path) && self.extractor.is_asset(&path) =>
+                {
+                    created_assets.push(path)
+                }
+                _ => {}
+            }
+        }
+
+        if !config_changed
+            && created.is_empty()
+            && created_assets.is_empty()
+            && changed.is_empty()
+            && deleted.is_empty()
+        {
+            return GraphDiff::default();
+        }
+        if config_changed
+            || !created.is_empty()
+            || !created_assets.is_empty()
+            || !deleted.is_empty()
+        {
+            self.extractor.clear_resolver_cache();
+        }
+
+        // Same IDs as a fresh build, which adds files in path order.
+        for path in self.files_to_rekey(&created, &deleted) {
+            let known = &self.files[&path];
+            if let Some(mut node) = self.extractor.node(known.file_path.clone()) {
+                node.position = known.position;
+                deleted.push(path.clone());
+                created.push((path, node));
+            }
+        }
+        created.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let created_paths = created
+            .iter()
+            .map(|(path, _)| path.clone())
+            .chain(created_assets)
+            .collect::<Vec<_>>();
+        let mut stale = self.possibly_affected_files(&created_paths, &deleted);
+        let mut diff = GraphDiff::default();
+
+        for path in &deleted {
+            self.imports.remove(path);
+            if let Some(node) = self.files.remove(path)
+                && let Some((node, edges)) = self.graph.remove_node(node.id)
+            {
+                diff.removed_nodes.push(node);
+                diff.removed_edges.extend(edges);
+            }
+        }
+        for (path, mut node) in created {
+            self.resolve_id_collision(&mut node);
+            self.graph.add_node(node.clone());
+            self.files.insert(path.clone(), node.clone());
+            diff.added_nodes.push(node);
+            stale.insert(path);
+        }
+        for (path, node) in changed {
+            self.graph.add_node(node.clone());
+            self.files.insert(path.clone(), node);
+            stale.insert(path);
+        }
+
+        if config_changed {
+            stale = self.files.keys().cloned().collect();
+        }
+        stale.retain(|path| self.files.contains_key(path));
+
+        let stale_ids = stale
+            .iter()
+            .map(|path| self.files[path].id)
+            .collect::<HashSet<_>>();
+        let old_edges = self
+            .graph
+            .iter_edges()
+            .filter(|edge| stale_ids.contains(&edge.from))
+            .cloned()
+            .collect::<HashSet<_>>();
+        for edge in &old_edges {
+            self.graph.remove_edge(edge);
+        }
+
+        let stale = stale.into_iter().collect::<Vec<_>>();
+        for path in &stale {
+            self.imports.remove(path);
+        }
+        self.extract_imports(&stale);
+        diff.added_nodes.extend(self.add_external_packages(&stale));
+        diff.added_nodes.extend(self.add_assets(&stale));
+        let new_edges = stale
+            .iter()
+            .flat_map(|path| self.edges_from(path))
+            .collect::<HashSet<_>>();
+
+        diff.removed_edges
+            .extend(old_edges.difference(&new_edges).cloned());
+        diff.added_edges
+            .extend(new_edges.difference(&old_edges).cloned());
+        for edge in new_edges {
+            self.graph.add_edge(edge);
+        }
+        diff.removed_nodes
+            .extend(self.remove_unused_external_packages());
+        diff.removed_nodes.extend(self.remove_unused_assets());
+
+        diff
+    }
+
+    /// Known files whose ID changes once `created` exist and `deleted` are gone, as the smaller
+    /// of two colliding paths keeps the plain hash: files colliding with a smaller created path,
+    /// and files with a salted ID whose plain hash belonged to a deleted file.
+    fn files_to_rekey(&self, created: &[(PathBuf, Node)], deleted: &[PathBuf]) -> Vec<PathBuf> {
+        let mut rekeyed = created
+            .iter()
+            .filter(|(_, node)| self.graph.nodes.contains_key(&node.id))
+            .filter_map(|(created_path, node)| {
+                self.files
+                    .iter()
+                    .find(|(path, known)| known.id == node.id && *path > created_path)
+                    .map(|(path, _)| path.clone())
+            })
+            .collect::<HashSet<_>>();
+        let deleted_ids = deleted
+            .iter()
+            .filter_map(|path| self.files.get(path))
+            .map(|node| node.id)
+            .collect::<HashSet<_>>();
+        if !deleted_ids.is_empty() {
+            rekeyed.extend(
+                self.files
+                    .iter()
+                    .filter(|(_, known)| {
+                        let id = Node::id_for(self.project_root(), &known.file_path);
+                        known.id != id && deleted_ids.contains(&id)
+                    })
+                    .map(|(path, _)| path.clone()),
+            );
+        }
+
+        let mut rekeyed = rekeyed
+            .into_iter()
+            .filter(|path| !deleted.contains(path))
+            .collect::<Vec<_>>();
+        rekeyed.sort_unstable();
+        rekeyed
+    }
+
+    /// Files whose imports may resolve differently once `created` exist and `deleted` are gone:
+    /// importers of deleted files, files with unresolved imports, and importers of files that a
+    /// created file could shadow (`./foo` resolving to `foo.ts` instead of `foo/index.ts`, or
+    /// `foo.ts` instead of `foo.js`).
+    pub(super) fn possibly_affected_files(
+        &self,
+        created: &[PathBuf],
+        deleted: &[PathBuf],
+    ) -> HashSet<PathBuf> {
+        let mut targets = deleted.iter().collect::<HashSet<&PathBuf>>();
+
+        let mut affected = HashSet::new();
+        if !created.is_empty() {
+            affected.extend(
+                self.imports
+                    .iter()
+                    .filter(|(_, imports)| imports.has_unresolved_imports())
+                    .map(|(path, _)| path.clone()),
+            );
+
+            for created_path in created {
+                let (Some(dir), Some(stem)) = (created_path.parent(), created_path.file_stem())
+                else {
+                    continue;
+                };
+                let shadowed_dir = dir.join(stem);
+                targets.extend(
+                    self.files.keys().filter(|path| {
+                        path.parent() == Some(dir) || path.starts_with(&shadowed_dir)
+                    }),
+                );
+            }
+        }
+
+        affected.extend(
+            self.imports
+                .iter()
+                .filter(|(_, imports)| {
+                    imports
+                        .resolved
+                        .iter()
+                        .any(|import| targets.contains(&import.path))
+                })
+                .map(|(path, _)| path.clone()),
+        );
+
+        affected
+    }
+}
+
+pub(super) fn is_resolution_config(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|name| name.to_str()),
+        Some(name) if name == "package.json"
+            || name == "jsconfig.json"
+            || (name.starts_with("tsconfig") && name.ends_with(".json"))
+    )
+}
+
+/// Canonicalizes the longest existing ancestor of `path`, so that deleted files map to the
+/// same key as when they were discovered.
+pub(super) fn canonicalize(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(canonical) = current.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |acc, component| acc.join(component));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::Node;
+    use crate::graph_builders::Typescript;
+    use test_log::test;
+
+    #[test]
+    fn it_updates_changed_created_and_deleted_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("a.ts"), "import { b } from './b';\n").unwrap();
+        std::fs::write(root.join("b.ts"), "export const b = 1;\n").unwrap();
+        std::fs::write(root.join("c.ts"), "import { d } from './d';\n").unwrap();
+
+        let mut typescript = Typescript::new(root.clone());
+        assert_eq!(typescript.graph.iter_nodes().count(), 3);
+        assert_eq!(typescript.graph.iter_edges().count(), 1);
+
+        let diff = typescript.update(vec![root.join("a.ts"), root.join("b.ts")]);
+        assert!(diff.is_empty());
+
+        std::fs::write(root.join("d.ts"), "export const d = 1;\n").unwrap();
+        let diff = typescript.update(vec![root.join("d.ts")]);
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_edges.len(), 1);
+        assert_eq!(typescript.graph.iter_edges().count(), 2);
+
+        std::fs::write(root.join("a.ts"), "import { d } from './d';\n// changed\n").unwrap();
+        std::fs::remove_file(root.join("b.ts")).unwrap();
+        let diff = typescript.update(vec![root.join("a.ts"), root.join("b.ts")]);
+        assert_eq!(diff.removed_nodes.len(), 1);
+        assert_eq!(diff.removed_edges.len(), 1);
+        assert_eq!(diff.added_edges.len(), 1);
+        assert_eq!(typescript.graph.iter_nodes().count(), 3);
+        assert_eq!(typescript.graph.iter_edges().count(), 2);
+    }
+
+    #[test]
+    fn it_resolves_every_file_again_when_a_config_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.ts"), "import { b } from '@/b';\n").unwrap();
+        std::fs::write(root.join("src/b.ts"), "export const b = 1;\n").unwrap();
+
+        let mut typescript = Typescript::new(root.clone());
+        assert_eq!(typescript.graph.iter_edges().count(), 0);
+
+        std::fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "compilerOptions": { "paths": { "@/*": ["./src/*"] } } }"#,
+        )
+        .unwrap();
+        let diff = typescript.update(vec![root.join("tsconfig.json")]);
+        assert!(diff.added_nodes.is_empty());
+        assert_eq!(diff.added_edges.len(), 1);
+        assert!(typescript.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn it_updates_created_and_deleted_directories() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("a.ts"), "import { b } from './lib/b';\n").unwrap();
+
+        let mut typescript = Typescript::new(root.clone());
+        assert_eq!(typescript.graph.iter_nodes().count(), 1);
+
+        std::fs::create_dir_all(root.join("lib/nested")).unwrap();
+        std::fs::write(root.join("lib/b.ts"), "import { c } from './nested/c';\n").unwrap();
+        std::fs::write(root.join("lib/nested/c.ts"), "export const c = 1;\n").unwrap();
+        let diff = typescript.update(vec![root.join("lib")]);
+        assert_eq!(diff.added_nodes.len(), 2);
+        assert_eq!(diff.added_edges.len(), 2);
+
+        std::fs::remove_dir_all(root.join("lib")).unwrap();
+        let diff = typescript.update(vec![root.join("lib")]);
+        assert_eq!(diff.removed_nodes.len(), 2);
+        assert_eq!(diff.removed_edges.len(), 2);
+        assert_eq!(typescript.graph.iter_nodes().count(), 1);
+        assert_eq!(typescript.diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn it_resolves_imports_again_when_a_created_file_shadows_a_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("foo")).unwrap();
+        std::fs::write(root.join("a.ts"), "import { foo } from './foo';\n").unwrap();
+        std::fs::write(root.join("foo/index.ts"), "export const foo = 1;\n").unwrap();
+
+        let mut typescript = Typescript::new(root.clone());
+        let id = |path: &str| typescript.files[&root.join(path)].id;
+        let edge = typescript.graph.iter_edges().next().unwrap();
+        assert_eq!((edge.from, edge.to), (id("a.ts"), id("foo/index.ts")));
+
+        std::fs::write(root.join("foo.ts"), "export const foo = 2;\n").unwrap();
+        let diff = typescript.update(vec![root.join("foo.ts")]);
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.removed_edges.len(), 1);
+        assert_eq!(diff.added_edges.len(), 1);
+        let id = |path: &str| typescript.files[&root.join(path)].id;
+        assert_eq!(diff.added_edges[0].to, id("foo.ts"));
+    }
+
+    #[test]
+    fn it_gives_colliding_files_the_ids_of_a_fresh_build() {
+        // Both paths hash to the same ID.
+        let (smaller, greater) = ("m7d62885921.ts", "mea63a10a7e.ts");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let ids = |typescript: &Typescript| {
+            [smaller, greater].map(|path| typescript.files.get(&root.join(path)).map(|n| n.id))
+        };
+        std::fs::write(root.join("index.ts"), format!("import './{smaller}';\n")).unwrap();
+        std::fs::write(root.join(smaller), "").unwrap();
+        std::fs::write(root.join(greater), "").unwrap();
+        let fresh = ids(&Typescript::new(root.clone()));
+        assert_eq!(fresh[0], Some(Node::id_for(&root, &root.join(smaller))));
+        assert_eq!(Node::id_for(&root, &root.join(greater)), fresh[0].unwrap());
+        assert_ne!(fresh[1], fresh[0]);
+
+        std::fs::remove_file(root.join(smaller)).unwrap();
+        let mut typescript = Typescript::new(root.clone());
+        std::fs::write(root.join(smaller), "").unwrap();
+        let diff = typescript.update(vec![root.join(smaller)]);
+        assert_eq!(ids(&typescript), fresh);
+        assert_eq!(diff.added_edges.len(), 1);
+        assert_eq!(typescript.graph.iter_nodes().count(), 3);
+
+        std::fs::remove_file(root.join(smaller)).unwrap();
+        typescript.update(vec![root.join(smaller)]);
+        assert_eq!(ids(&typescript), [None, fresh[0]]);
+
+        std::fs::remove_file(root.join(greater)).unwrap();
+        let mut typescript = Typescript::new(root.clone());
+        std::fs::write(root.join(greater), "").unwrap();
+        std::fs::write(root.join(smaller), "").unwrap();
+        typescript.update(vec![root.join(greater), root.join(smaller)]);
+        assert_eq!(ids(&typescript), fresh);
+    }
+}
@@ -1,15 +1,84 @@
 use crate::{
-    graph::{Edge, Graph, Node},
+    diagnostics::Diagnostic,
+    graph::{Edge, Graph, Node, NodeKind},
     graph_builders::GraphBuilder,
 };
+use globset::{Glob, GlobSetBuilder};
 use rayon::prelude::*;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
+mod assets;
+mod cache;
+mod entry_points;
+mod external;
 mod extractor;
+mod impact;
+mod incremental;
+mod packages;
+mod revisions;
+mod stylesheet;
 mod visitor;
 
+pub use impact::{DEFAULT_TEST_PATTERNS, Impact};
+pub use tuan_walker::DEFAULT_EXCLUDE;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypescriptOptions {
+    /// Only files whose project-relative path matches one of these globs are part of the
+    /// graph. Empty means every file.
+    pub include: Vec<String>,
+    /// Files and directories whose project-relative path matches one of these globs are
+    /// skipped. Defaults to [`DEFAULT_EXCLUDE`].
+    pub exclude: Vec<String>,
+    /// Extensions parsed on top of `ts`, `tsx`, `js`, `jsx`, `mjs`, `cjs`, `vue`, `svelte` and
+    /// `astro`, e.g. `mts`.
+    pub extensions: Vec<String>,
+    /// Files larger than this many bytes are skipped, e.g. bundled vendor files.
+    pub max_file_size: Option<u64>,
+    pub follow_symlinks: bool,
+    /// Whether `.d.ts` files are part of the graph.
+    pub include_declarations: bool,
+    /// Adds a node per npm package imported from `node_modules`, instead of dropping those
+    /// imports.
+    pub external_packages: bool,
+    /// Adds stylesheets to the graph along with their `@import`, `@use`, `@forward` and `url()`
+    /// references, and a node per imported image, font, JSON file or other non-code file.
+    pub assets: bool,
+}
+
+impl Default for TypescriptOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: DEFAULT_EXCLUDE
+                .iter()
+                .map(|glob| glob.to_string())
+                .collect(),
+            extensions: Vec::new(),
+            max_file_size: None,
+            follow_symlinks: false,
+            include_declarations: true,
+            external_packages: false,
+            assets: false,
+        }
+    }
+}
+
 pub struct Typescript {
     graph: Graph,
+    extractor: extractor::Extractor,
+    files: HashMap<PathBuf, Node>,
+    imports: HashMap<PathBuf, extractor::ExtractedImports>,
+    /// External package nodes, keyed by package directory.
+    externals: HashMap<PathBuf, Node>,
+    /// Asset nodes, keyed by canonical path.
+    assets: HashMap<PathBuf, Node>,
+    options: TypescriptOptions,
+    cache_dir: Option<PathBuf>,
 }
 
 impl GraphBuilder for Typescript {
@@ -20,65 +89,241 @@ impl GraphBuilder for Typescript {
 
 impl Typescript {
     pub fn new(project_path: PathBuf) -> Self {
-        let graph = Self::create_graph(project_path);
-        Self { graph }
+        Self::with_options(project_path, TypescriptOptions::default())
+    }
+
+    pub fn with_options(project_path: PathBuf, options: TypescriptOptions) -> Self {
+        let mut typescript = Self::discover(project_path, None, options);
+        typescript.create_graph();
+        typescript
     }
 
-    fn create_graph(project_path: PathBuf) -> Graph {
-        let mut graph = Graph::new();
+    /// Like [`Typescript::with_options`], but reuses the imports stored in `cache_dir` for files whose
+    /// fingerprint did not change, and writes the cache back once the graph is built.
+    pub fn with_cache(
+        project_path: PathBuf,
+        cache_dir: PathBuf,
+        options: TypescriptOptions,
+    ) -> Self {
+        let mut typescript = Self::discover(project_path, Some(cache_dir), options);
+        typescript.load_cache();
+        typescript.create_graph();
+        if let Err(e) = typescript.save_cache() {
+            tracing::warn!("Failed to write the graph cache: {}", e);
+        }
+        typescript
+    }
+
+    pub fn project_root(&self) -> &Path {
+        &self.extractor.project_root
+    }
+
+    /// Whether `path` is skipped by file discovery, e.g. because it lives in `node_modules`.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.extractor.is_excluded(path)
+    }
+
+    /// Whether a change of `path` may change the graph: source files discovery would pick up,
+    /// known files, assets, directories and resolution configs. Other changes can be left out
+    /// of [`Typescript::update`].
+    pub fn is_relevant_change(&self, path: &Path) -> bool {
+        if self.is_ignored(path) {
+            return false;
+        }
+        let path = incremental::canonicalize(path);
+        if incremental::is_resolution_config(&path) || path.is_dir() {
+            return true;
+        }
+        if !path.exists() {
+            return self
+                .files
+                .keys()
+                .chain(self.assets.keys())
+                .any(|known| known.starts_with(&path));
+        }
+        self.files.contains_key(&path)
+            || self.extractor.is_discoverable(&path)
+            || self.extractor.is_asset(&path)
+    }
+
+    /// `dir` and every directory below it that discovery enters, for watching the project
+    /// without watching `node_modules` or other ignored directories.
+    pub fn directories(&self, dir: &Path) -> Vec<PathBuf> {
+        self.extractor.find_directories_in(dir)
+    }
+
+    /// Unresolved imports, parse errors and unreadable files, ordered by file and location.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = self
+            .imports
+            .values()
+            .flat_map(|imports| imports.diagnostics.iter().cloned())
+            .collect::<Vec<_>>();
+        diagnostics.sort_by(|a, b| {
+            (&a.file_path, a.location.map(|l| l.start))
+                .cmp(&(&b.file_path, b.location.map(|l| l.start)))
+        });
+        diagnostics
+    }
+
+    /// Discovered files whose project-relative path matches one of `globs`.
+    fn files_matching<S: AsRef<str>>(
+        &self,
+        globs: &[S],
+    ) -> Result<impl Iterator<Item = &Node>, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for glob in globs {
+            builder.add(Glob::new(glob.as_ref())?);
+        }
+        let glob_set = builder.build()?;
+
+        Ok(self.files.iter().filter_map(move |(path, node)| {
+            let relative_path = path.strip_prefix(self.project_root()).unwrap_or(path);
+            glob_set.is_match(relative_path).then_some(node)
+        }))
+    }
 
-        let project_path = project_path;
-        let extractor = extractor::Extractor::new(&project_path);
-        let ts_files = {
+    fn discover(
+        project_path: PathBuf,
+        cache_dir: Option<PathBuf>,
+        options: TypescriptOptions,
+    ) -> Self {
+        let extractor = extractor::Extractor::new(&project_path, &options);
+        let files = {
             measure_time::info_time!("Finding TypeScript files");
-            extractor.find_typescript_files(&project_path)
+            extractor.find_typescript_files()
         };
 
-        let results: Vec<(Node, Vec<Edge>)> = {
+        Self {
+            graph: Graph::new(),
+            extractor,
+            files,
+            imports: HashMap::new(),
+            externals: HashMap::new(),
+            assets: HashMap::new(),
+            options,
+            cache_dir,
+        }
+    }
+
+    fn create_graph(&mut self) {
+        let paths = self
+            .files
+            .keys()
+            .filter(|path| !self.imports.contains_key(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        {
             measure_time::info_time!("Extracting imports from TypeScript files");
-            ts_files
-                .par_iter()
-                .map(|(_, file)| {
-                    let mut edges = Vec::new();
-                    match extractor.extract_typescript_imports(file) {
-                        Ok(imports) => {
-                            for imported_file in imports {
-                                // TODO: support imports with ? (like import x from 'y?type=script')
-                                if let Some(import_node) = ts_files.get(&imported_file.key) {
-                                    edges.push(Edge::new(file.id.clone(), import_node.id.clone()));
-                                }
-                            }
-                        }
-                        Err(e) => tracing::error!(
-                            "Error extracting imports from {}: {}",
-                            file.file_path.display(),
-                            e
-                        ),
-                    }
-                    (file.clone(), edges)
-                })
-                .collect()
-        };
+            self.extract_imports(&paths);
+        }
 
         {
             measure_time::info_time!("Inserting nodes and edges into graph");
-            for (file, _edges) in &results {
-                graph.add_node(file.clone());
+            // Sorted, so that colliding paths get the same IDs on every build.
+            let mut paths = self.files.keys().cloned().collect::<Vec<_>>();
+            paths.sort_unstable();
+            for path in &paths {
+                let mut node = self.files[path].clone();
+                self.resolve_id_collision(&mut node);
+                self.files.insert(path.clone(), node.clone());
+                self.graph.add_node(node);
             }
-            for (_file, edges) in &results {
-                for edge in edges {
-                    graph.add_edge(edge.clone());
-                }
+            self.add_external_packages(&paths);
+            self.add_assets(&paths);
+            let edges = self
+                .files
+                .keys()
+                .flat_map(|path| self.edges_from(path))
+                .collect::<Vec<_>>();
+            for edge in edges {
+                self.graph.add_edge(edge);
             }
         }
+    }
+
+    /// Gives `node` another ID when its ID already belongs to a different node, see
+    /// [`Node::unique_id_for`]. Packages with the same name and version share their node.
+    pub(super) fn resolve_id_collision(&self, node: &mut Node) {
+        let is_same_node = |other: &Node| {
+            other.file_path == node.file_path
+                || (other.kind == NodeKind::ExternalPackage
+                    && node.kind == NodeKind::ExternalPackage
+                    && other.label == node.label)
+        };
+        let is_taken = |id| {
+            self.graph
+                .nodes
+                .get(&id)
+                .is_some_and(|other| !is_same_node(other))
+        };
+        if !is_taken(node.id) {
+            return;
+        }
 
-        graph
+        let key_path = match node.kind {
+            NodeKind::ExternalPackage => Path::new("node_modules").join(&node.label),
+            _ => node.file_path.clone(),
+        };
+        let id = Node::unique_id_for(self.project_root(), &key_path, is_taken);
+        tracing::debug!(
+            "Node ID collision for {}, using {} instead of {}",
+            node.file_path.display(),
+            id,
+            node.id
+        );
+        node.id = id;
+    }
+
+    fn extract_imports(&mut self, paths: &[PathBuf]) {
+        let results = paths
+            .par_iter()
+            .filter_map(|path| {
+                let file = self.files.get(path)?;
+                let imports = self
+                    .extractor
+                    .extract_typescript_imports(file)
+                    .unwrap_or_else(|e| extractor::ExtractedImports::unreadable(file, e.as_ref()));
+                Some((path.clone(), imports))
+            })
+            .collect::<Vec<_>>();
+        self.imports.extend(results);
+    }
+
+    /// Outgoing edges of a file. Imports pointing outside of the discovered files are dropped,
+    /// unless they point into a known external package or to a known asset.
+    fn edges_from(&self, path: &Path) -> Vec<Edge> {
+        let (Some(file), Some(imports)) = (self.files.get(path), self.imports.get(path)) else {
+            return Vec::new();
+        };
+
+        imports
+            .resolved
+            .iter()
+            .filter_map(|import| {
+                let import_node = self
+                    .files
+                    .get(&import.path)
+                    .or_else(|| self.external_package(&import.path))
+                    .or_else(|| self.assets.get(&import.path))?;
+                Some(Edge {
+                    symbols: import.symbols.clone(),
+                    location: Some(import.location),
+                    suffix: import.suffix.clone(),
+                    ..Edge::new(file.id, import_node.id, import.kind)
+                })
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        diagnostics::{DiagnosticKind, Severity},
+        graph::EdgeKind,
+    };
     use test_log::test;
     use tracing::info;
 
@@ -106,8 +351,8 @@ mod tests {
         info!("Graph has {} nodes", graph.iter_nodes().count());
         info!("Graph has {} edges", graph.iter_edges().count());
 
-        assert!(graph.iter_nodes().count() == 7745);
-        assert!(graph.iter_edges().count() == 5389);
+        assert_eq!(graph.iter_nodes().count(), 7745);
+        assert_eq!(graph.iter_edges().count(), 5389);
 
         drop(temp_dir);
 
@@ -117,6 +362,298 @@ mod tests {
         }
     }
 
+    #[test]
+    fn it_keeps_files_with_identical_size_and_mtime_apart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let names = ["a", "b", "c", "d", "e"];
+        let modified =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+
+        for (idx, name) in names.iter().enumerate() {
+            let next = names[(idx + 1) % names.len()];
+            let path = temp_dir.path().join(format!("{name}.ts"));
+            std::fs::write(
+                &path,
+                format!("import {{ {next} }} from './{next}';\nexport const {name} = 1;\n"),
+            )
+            .unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        let graph = Typescript::new(temp_dir.path().to_path_buf()).get_graph();
+
+        assert_eq!(graph.iter_nodes().count(), names.len());
+        assert_eq!(graph.iter_edges().count(), names.len());
+
+        for edge in graph.iter_edges() {
+            let from = graph.nodes[&edge.from]
+                .file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            let to = graph.nodes[&edge.to]
+                .file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            let from_idx = names.iter().position(|name| *name == from).unwrap();
+            assert_eq!(to, names[(from_idx + 1) % names.len()]);
+        }
+    }
+
+    #[test]
+    fn it_reports_unresolved_imports_and_parse_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("a.ts"),
+            "import { b } from './b';\nimport { c } from './c';\nimport x from 'not-installed';\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("b.ts"),
+            "import { a } from './a';\nexport const = 1;\n",
+        )
+        .unwrap();
+
+        let typescript = Typescript::new(root.clone());
+        let diagnostics = typescript.diagnostics();
+
+        assert_eq!(typescript.get_graph().iter_edges().count(), 1);
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnresolvedImport);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].specifier.as_deref(), Some("./c"));
+        assert_eq!(diagnostics[0].location.unwrap().line, 2);
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].specifier.as_deref(), Some("not-installed"));
+        assert_eq!(diagnostics[2].kind, DiagnosticKind::ParseError);
+        assert_eq!(diagnostics[2].file_path, root.join("b.ts"));
+        assert_eq!(diagnostics[2].location.unwrap().line, 2);
+    }
+
+    #[test]
+    fn it_strips_query_and_hash_suffixes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("a.ts"),
+            "import Worker from './w?worker';\nimport b from './b?raw';\nimport { c } from './c#c';\nimport type { W } from './w?worker&inline';\n",
+        )
+        .unwrap();
+        for name in ["w.ts", "b.ts", "c.ts"] {
+            std::fs::write(root.join(name), "export {};\n").unwrap();
+        }
+
+        let typescript = Typescript::new(root.clone());
+        let graph = typescript.get_graph();
+        let mut edges = graph
+            .iter_edges()
+            .map(|edge| {
+                let to = graph.nodes[&edge.to].label.clone();
+                (to, edge.kind, edge.suffix.clone())
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+
+        assert!(typescript.diagnostics().is_empty());
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    "b.ts".to_string(),
+                    EdgeKind::Asset,
+                    Some("?raw".to_string())
+                ),
+                ("c.ts".to_string(), EdgeKind::Static, Some("#c".to_string())),
+                (
+                    "w.ts".to_string(),
+                    EdgeKind::TypeOnly,
+                    Some("?worker&inline".to_string())
+                ),
+                (
+                    "w.ts".to_string(),
+                    EdgeKind::Worker,
+                    Some("?worker".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_extracts_imports_from_vue_components() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("main.ts"),
+            "import App from './App.vue';\nimport { a } from './a';\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("App.vue"),
+            "<template><Button /></template>\n<script lang=\"ts\">\nimport { a } from './a';\n</script>\n<script setup lang=\"ts\">\nimport Button from './Button.vue';\n</script>\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("Button.vue"), "<template><button /></template>\n").unwrap();
+        std::fs::write(root.join("a.ts"), "export const a = 1;\n").unwrap();
+
+        let typescript = Typescript::new(root.clone());
+        let graph = typescript.get_graph();
+        let mut edges = graph
+            .iter_edges()
+            .map(|edge| {
+                (
+                    graph.nodes[&edge.from].label.as_str(),
+                    graph.nodes[&edge.to].label.as_str(),
+                    edge.location.unwrap().line,
+                )
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+
+        assert!(typescript.diagnostics().is_empty());
+        assert_eq!(
+            edges,
+            vec![
+                ("App.vue", "Button.vue", 6),
+                ("App.vue", "a.ts", 3),
+                ("main.ts", "App.vue", 1),
+                ("main.ts", "a.ts", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_extracts_imports_from_svelte_and_astro_components() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("Widget.svelte"),
+            "<script context=\"module\" lang=\"ts\">\nimport { a } from './a';\n</script>\n<script>\nimport Button from './Button.svelte';\n</script>\n<Button />\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("Button.svelte"), "<button />\n").unwrap();
+        std::fs::write(
+            root.join("index.astro"),
+            "---\nimport Widget from './Widget.svelte';\nif (!Widget) return Astro.redirect('/');\n---\n<Widget client:load />\n<script>\nimport { a } from './a';\n</script>\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("a.ts"), "export const a = 1;\n").unwrap();
+
+        let typescript = Typescript::new(root.clone());
+        let graph = typescript.get_graph();
+        let mut edges = graph
+            .iter_edges()
+            .map(|edge| {
+                (
+                    graph.nodes[&edge.from].label.as_str(),
+                    graph.nodes[&edge.to].label.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+
+        assert!(typescript.diagnostics().is_empty());
+        assert_eq!(graph.iter_nodes().count(), 4);
+        assert_eq!(
+            edges,
+            vec![
+                ("Widget.svelte", "Button.svelte"),
+                ("Widget.svelte", "a.ts"),
+                ("index.astro", "Widget.svelte"),
+                ("index.astro", "a.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_applies_discovery_options() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("src/index.ts", "import { a } from '../build/a';\n");
+        write("build/a.ts", "export const a = 1;\n");
+        write(".next/chunk.js", "export const chunk = 1;\n");
+        write("src/types.d.ts", "declare const x: number;\n");
+        write("src/worker.mts", "export const worker = 1;\n");
+        write("src/vendor.js", &"// padding\n".repeat(100));
+        write("scripts/release.ts", "export {};\n");
+
+        let labels = |options: TypescriptOptions| {
+            let mut labels = Typescript::with_options(root.clone(), options)
+                .get_graph()
+                .iter_nodes()
+                .map(|node| node.label.clone())
+                .collect::<Vec<_>>();
+            labels.sort_unstable();
+            labels
+        };
+
+        assert_eq!(
+            labels(TypescriptOptions::default()),
+            vec!["index.ts", "release.ts", "types.d.ts", "vendor.js"]
+        );
+        assert_eq!(
+            labels(TypescriptOptions {
+                include: vec!["src/**".to_string(), "build/**".to_string()],
+                exclude: vec![".next".to_string()],
+                extensions: vec![".mts".to_string()],
+                max_file_size: Some(100),
+                include_declarations: false,
+                ..Default::default()
+            }),
+            vec!["a.ts", "index.ts", "worker.mts"]
+        );
+    }
+
+    #[test]
+    fn it_skips_ignored_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(".gitignore", "generated/\n");
+        write(tuan_walker::IGNORE_FILE_NAME, "scratch.ts\n");
+        write(
+            "src/index.ts",
+            "import { client } from '../generated/client';\n",
+        );
+        write("src/scratch.ts", "");
+        write("generated/client.ts", "export const client = 1;\n");
+
+        let mut typescript = Typescript::new(root.clone());
+        let labels = typescript
+            .get_graph()
+            .iter_nodes()
+            .map(|node| node.label.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["index.ts"]);
+
+        write("generated/other.ts", "");
+        write("src/other.ts", "");
+        let diff = typescript.update(vec![
+            root.join("generated/other.ts"),
+            root.join("src/other.ts"),
+        ]);
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_nodes[0].label, "other.ts");
+        assert_eq!(diff.added_nodes[0].file_path, root.join("src/other.ts"));
+    }
+
     fn setup_test_project(git_repo: &str, commit: &str) -> (PathBuf, tempfile::TempDir) {
         let temp_dir = tempfile::tempdir().unwrap();
         let fixture_dir = temp_dir.path();
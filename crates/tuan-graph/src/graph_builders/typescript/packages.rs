@@ -0,0 +1,67 @@
+use super::Typescript;
+use crate::packages::PackageGraph;
+use serde_json::Value;
+
+impl Typescript {
+    /// Aggregates the file graph by workspace package, each file belonging to the package of
+    /// its nearest enclosing `package.json`. Packages without a `name` are named after their
+    /// project-relative directory.
+    pub fn package_graph(&self) -> PackageGraph {
+        let packages = self
+            .extractor
+            .find_files_named("package.json")
+            .into_iter()
+            .filter_map(|manifest| {
+                let root = manifest.parent()?.to_path_buf();
+                let name = std::fs::read_to_string(&manifest)
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                    .and_then(|package| package.get("name")?.as_str().map(str::to_string))
+                    .unwrap_or_else(|| {
+                        let relative_root = root.strip_prefix(self.project_root()).unwrap_or(&root);
+                        match relative_root.to_string_lossy() {
+                            name if name.is_empty() => ".".to_string(),
+                            name => name.replace('\\', "/"),
+                        }
+                    });
+                Some((name, root))
+            })
+            .collect::<Vec<_>>();
+
+        self.graph.package_graph(&packages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_builders::Typescript;
+    use test_log::test;
+
+    #[test]
+    fn it_builds_a_package_graph_from_package_json_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("package.json", "{}");
+        write("packages/app/package.json", r#"{ "name": "app" }"#);
+        write("packages/app/main.ts", "import '../lib/index';\n");
+        write("packages/lib/package.json", r#"{ "name": "lib" }"#);
+        write("packages/lib/index.ts", "");
+        write("tools/build.ts", "import '../packages/lib/index';\n");
+
+        let package_graph = Typescript::new(root.clone()).package_graph();
+
+        let names = package_graph
+            .packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![".", "app", "lib"]);
+        assert_eq!(package_graph.edges.len(), 2);
+        assert!(package_graph.edges.iter().all(|edge| edge.to == 2));
+    }
+}
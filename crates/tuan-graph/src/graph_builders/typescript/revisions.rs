@@ -0,0 +1,203 @@
+use super::{Typescript, TypescriptOptions};
+use crate::{
+    compare::GraphComparison, cycles::CycleOptions, graph::Graph, graph_builders::GraphBuilder,
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static WORKTREE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl Typescript {
+    /// Builds the graph of `project_path` at the `base` and `head` revisions of its git
+    /// repository and compares them. Each revision is checked out in a temporary worktree, so
+    /// the working copy is left untouched. Worktrees have no `node_modules`, so imports of
+    /// installed packages do not resolve.
+    pub fn compare_revisions(
+        project_path: &Path,
+        base: &str,
+        head: &str,
+        options: TypescriptOptions,
+        cycle_options: CycleOptions,
+    ) -> io::Result<GraphComparison> {
+        measure_time::info_time!("Comparing revisions {} and {}", base, head);
+
+        let repository_root = PathBuf::from(git(project_path, &["rev-parse", "--show-toplevel"])?);
+        let repository_root = repository_root.canonicalize()?;
+        let project_path = project_path.canonicalize()?;
+        let project_dir = project_path
+            .strip_prefix(&repository_root)
+            .unwrap_or(Path::new(""));
+
+        let (base_root, base_graph) =
+            graph_at_revision(&repository_root, project_dir, base, &options)?;
+        let (head_root, head_graph) =
+            graph_at_revision(&repository_root, project_dir, head, &options)?;
+
+        Ok(base_graph.compare(&base_root, &head_graph, &head_root, cycle_options))
+    }
+}
+
+/// The worktree is removed before returning, so only project-relative paths of the graph
+/// are meaningful.
+fn graph_at_revision(
+    repository_root: &Path,
+    project_dir: &Path,
+    revision: &str,
+    options: &TypescriptOptions,
+) -> io::Result<(PathBuf, Graph)> {
+    let worktree = Worktree::add(repository_root, revision)?;
+    let typescript = Typescript::with_options(worktree.path.join(project_dir), options.clone());
+    Ok((
+        typescript.project_root().to_path_buf(),
+        typescript.get_graph(),
+    ))
+}
+
+struct Worktree {
+    repository_root: PathBuf,
+    path: PathBuf,
+}
+
+impl Worktree {
+    fn add(repository_root: &Path, revision: &str) -> io::Result<Self> {
+        let commit = resolve_commit(repository_root, revision)?;
+        let path = std::env::temp_dir().join(format!(
+            "tuan-worktree-{}-{}",
+            std::process::id(),
+            WORKTREE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        git(
+            repository_root,
+            &[
+                "worktree",
+                "add",
+                "--detach",
+                &path.to_string_lossy(),
+                &commit,
+            ],
+        )?;
+        Ok(Self {
+            repository_root: repository_root.to_path_buf(),
+            path,
+        })
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let path = self.path.to_string_lossy();
+        if let Err(e) = git(
+            &self.repository_root,
+            &["worktree", "remove", "--force", &path],
+        ) {
+            tracing::warn!("Failed to remove worktree {}: {}", path, e);
+        }
+    }
+}
+
+/// The commit `revision` points to. `--end-of-options` keeps a revision starting with `-` from
+/// being taken for an option.
+fn resolve_commit(repository_root: &Path, revision: &str) -> io::Result<String> {
+    git(
+        repository_root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            "--end-of-options",
+            &format!("{revision}^{{commit}}"),
+        ],
+    )
+    .map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unknown git revision {revision}"),
+        )
+    })
+}
+
+/// Runs git in `dir` and returns its trimmed standard output.
+fn git(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn it_compares_two_revisions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let project = root.join("web");
+        std::fs::create_dir_all(&project).unwrap();
+        let commit = |message: &str| {
+            git(&root, &["add", "-A"]).unwrap();
+            git(
+                &root,
+                &[
+                    "-c",
+                    "user.name=test",
+                    "-c",
+                    "user.email=test@example.com",
+                    "commit",
+                    "-qm",
+                    message,
+                ],
+            )
+            .unwrap();
+        };
+        git(&root, &["init", "-q"]).unwrap();
+        std::fs::write(project.join("a.ts"), "import { b } from './b';\n").unwrap();
+        std::fs::write(project.join("b.ts"), "export const b = 1;\n").unwrap();
+        commit("base");
+        std::fs::write(project.join("b.ts"), "import { c } from './c';\n").unwrap();
+        std::fs::write(project.join("c.ts"), "import { a } from './a';\n").unwrap();
+        commit("head");
+
+        let comparison = Typescript::compare_revisions(
+            &project,
+            "HEAD~1",
+            "HEAD",
+            TypescriptOptions::default(),
+            CycleOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(comparison.added_files, vec![PathBuf::from("c.ts")]);
+        assert!(comparison.removed_files.is_empty());
+        assert_eq!(comparison.added_edges.len(), 2);
+        assert!(comparison.removed_edges.is_empty());
+        assert_eq!(comparison.new_cycles.len(), 1);
+        for revision in ["missing", "--orphan=x"] {
+            let error = Typescript::compare_revisions(
+                &project,
+                revision,
+                "HEAD",
+                TypescriptOptions::default(),
+                CycleOptions::default(),
+            )
+            .unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert!(
+            git(&root, &["worktree", "list", "--porcelain"])
+                .unwrap()
+                .lines()
+                .all(|line| !line.contains("tuan-worktree"))
+        );
+    }
+}
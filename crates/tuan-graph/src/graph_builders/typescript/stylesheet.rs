@@ -0,0 +1,241 @@
+use crate::graph::EdgeKind;
+use oxc_span::Span;
+use std::path::{Path, PathBuf};
+
+pub(super) const STYLESHEET_EXTENSIONS: [&str; 4] = ["css", "scss", "sass", "less"];
+
+/// A file referenced by a stylesheet.
+#[derive(Debug, PartialEq)]
+pub(super) struct Reference<'a> {
+    pub(super) specifier: &'a str,
+    pub(super) span: Span,
+    /// [`EdgeKind::Static`] for `@import`, `@use` and `@forward`, [`EdgeKind::Asset`] for
+    /// `url()`.
+    pub(super) kind: EdgeKind,
+}
+
+pub(super) fn is_stylesheet(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|extension| STYLESHEET_EXTENSIONS.contains(&extension))
+}
+
+/// `@import`, `@use`, `@forward` and `url()` references of a CSS, SCSS, Sass or Less file.
+/// Remote URLs, data URIs, absolute paths, fragments, Sass built-in modules and references
+/// built with interpolation are skipped.
+pub(super) fn references(source: &str) -> Vec<Reference<'_>> {
+    let bytes = source.as_bytes();
+    let mut references = Vec::new();
+    let mut push = |start: usize, end: usize, kind: EdgeKind| {
+        let raw = &source[start..end];
+        let specifier = raw.trim();
+        let start = start + raw.len() - raw.trim_start().len();
+        if is_local(specifier) {
+            references.push(Reference {
+                specifier,
+                span: Span::new(start as u32, (start + specifier.len()) as u32),
+                kind,
+            });
+        }
+    };
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = find(bytes, idx + 2, b"*/").map_or(bytes.len(), |end| end + 2);
+            }
+            // SCSS and Less line comments. `//` is not valid anywhere else outside of strings
+            // and `url()`.
+            b'/' if bytes.get(idx + 1) == Some(&b'/') => {
+                idx = find(bytes, idx, b"\n").unwrap_or(bytes.len());
+            }
+            b'"' | b'\'' => idx = string(bytes, idx).1,
+            b'@' => {
+                let name_start = idx + 1;
+                idx = name_start;
+                while bytes
+                    .get(idx)
+                    .is_some_and(|byte| byte.is_ascii_alphabetic() || *byte == b'-')
+                {
+                    idx += 1;
+                }
+                let name = &source[name_start..idx];
+                if !["import", "use", "forward"]
+                    .iter()
+                    .any(|rule| name.eq_ignore_ascii_case(rule))
+                {
+                    continue;
+                }
+
+                // `@import 'a', url(b.css) screen;` or Less' `@import (reference) 'a';`
+                loop {
+                    while bytes
+                        .get(idx)
+                        .is_some_and(|byte| byte.is_ascii_whitespace() || *byte == b',')
+                    {
+                        idx += 1;
+                    }
+                    match bytes.get(idx) {
+                        Some(b'"' | b'\'') => {
+                            let (end, next) = string(bytes, idx);
+                            push(idx + 1, end, EdgeKind::Static);
+                            idx = next;
+                        }
+                        Some(b'(') => {
+                            idx = find(bytes, idx, b")").map_or(bytes.len(), |end| end + 1)
+                        }
+                        Some(_) if is_url(bytes, idx) => {
+                            let (start, end, next) = url(bytes, idx);
+                            push(start, end, EdgeKind::Static);
+                            idx = next;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            b'u' | b'U' if is_url(bytes, idx) => {
+                let (start, end, next) = url(bytes, idx);
+                push(start, end, EdgeKind::Asset);
+                idx = next;
+            }
+            _ => idx += 1,
+        }
+    }
+
+    references
+}
+
+/// Paths a stylesheet reference may point to, relative to `dir`. Sass and Less imports may
+/// leave out the extension, Sass partials their leading underscore, and both may point to an
+/// `index` file.
+pub(super) fn candidates(dir: &Path, specifier: &str, extension: &str) -> Vec<PathBuf> {
+    let path = dir.join(specifier);
+    let mut candidates = vec![path.clone()];
+    let extensions: &[&str] = match extension {
+        "scss" | "sass" => &["scss", "sass", "css"],
+        "less" => &["less", "css"],
+        _ => return candidates,
+    };
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name().and_then(|s| s.to_str()))
+    else {
+        return candidates;
+    };
+
+    candidates.push(parent.join(format!("_{name}")));
+    for extension in extensions {
+        candidates.push(parent.join(format!("{name}.{extension}")));
+        candidates.push(parent.join(format!("_{name}.{extension}")));
+    }
+    for extension in extensions {
+        candidates.push(path.join(format!("index.{extension}")));
+        candidates.push(path.join(format!("_index.{extension}")));
+    }
+    candidates
+}
+
+fn is_local(specifier: &str) -> bool {
+    !specifier.is_empty()
+        && !specifier.starts_with(['/', '#'])
+        && !specifier.starts_with("data:")
+        && !specifier.starts_with("sass:")
+        && !specifier.contains("://")
+        && !specifier.contains(['$', '{', '('])
+}
+
+/// Whether `url(` starts at `idx`, and is not the end of another function name.
+fn is_url(bytes: &[u8], idx: usize) -> bool {
+    bytes
+        .get(idx..idx + 4)
+        .is_some_and(|name| name.eq_ignore_ascii_case(b"url("))
+        && (idx == 0
+            || !matches!(bytes[idx - 1], b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_'))
+}
+
+/// Parses the `url()` starting at `idx`, returning the range of its content and the index
+/// after the closing parenthesis.
+fn url(bytes: &[u8], idx: usize) -> (usize, usize, usize) {
+    let mut start = idx + 4;
+    while bytes.get(start).is_some_and(u8::is_ascii_whitespace) {
+        start += 1;
+    }
+    match bytes.get(start) {
+        Some(b'"' | b'\'') => {
+            let (end, next) = string(bytes, start);
+            let next = find(bytes, next, b")").map_or(bytes.len(), |close| close + 1);
+            (start + 1, end, next)
+        }
+        _ => {
+            let end = find(bytes, start, b")").unwrap_or(bytes.len());
+            (start, end, (end + 1).min(bytes.len()))
+        }
+    }
+}
+
+/// Parses the string starting at `idx`, returning the end of its content and the index after
+/// the closing quote. Unterminated strings end at the end of the line.
+fn string(bytes: &[u8], idx: usize) -> (usize, usize) {
+    let quote = bytes[idx];
+    let mut end = idx + 1;
+    while let Some(&byte) = bytes.get(end) {
+        match byte {
+            b'\\' => end += 2,
+            b'\n' => return (end, end),
+            _ if byte == quote => return (end, end + 1),
+            _ => end += 1,
+        }
+    }
+    (bytes.len(), bytes.len())
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn it_finds_stylesheet_references() {
+        let source = r#"@use 'sass:math';
+@use "variables" as vars;
+@forward 'mixins';
+@import 'reset.css', url("print.css") print;
+/* @import 'commented.css'; */
+// url(commented.png)
+.logo {
+  content: "url(not-a-url.png)";
+  background: url( ./images/logo.svg?inline ) no-repeat, url(data:image/png;base64,AAAA);
+  mask: url('#mask');
+  src: url("https://example.com/font.woff2"), url(/static/font.woff2), url(#{$dir}/x.png);
+}
+"#;
+
+        let references = references(source)
+            .into_iter()
+            .map(|reference| {
+                assert_eq!(
+                    &source[reference.span.start as usize..reference.span.end as usize],
+                    reference.specifier
+                );
+                (reference.specifier, reference.kind)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            vec![
+                ("variables", EdgeKind::Static),
+                ("mixins", EdgeKind::Static),
+                ("reset.css", EdgeKind::Static),
+                ("print.css", EdgeKind::Static),
+                ("./images/logo.svg?inline", EdgeKind::Asset),
+            ]
+        );
+    }
+}
@@ -1,55 +1,143 @@
-use crate::graph::Node;
+use crate::{
+    diagnostics::Diagnostic,
+    graph::{EdgeKind, SourceLocation},
+};
 use base64::prelude::*;
 use oxc_ast::ast::*;
-use oxc_ast_visit::Visit;
-use oxc_resolver::Resolver;
+use oxc_ast_visit::{Visit, walk};
+use oxc_resolver::{ResolveError, Resolver};
+use oxc_span::Span;
+use serde::{Deserialize, Serialize};
 use sourcemap::SourceMap;
 use std::path::{Path, PathBuf};
 
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct Import {
+    pub(super) path: PathBuf,
+    pub(super) kind: EdgeKind,
+    pub(super) symbols: Vec<String>,
+    pub(super) location: SourceLocation,
+    pub(super) suffix: Option<String>,
+}
+
 pub(super) struct Visitor<'a> {
-    pub(super) imports: Vec<Node>,
+    pub(super) imports: Vec<Import>,
+    pub(super) diagnostics: Vec<Diagnostic>,
+    current_file_path: PathBuf,
     current_file_dir: PathBuf,
+    source_code: &'a str,
+    line_starts: Vec<u32>,
     resolver: &'a Resolver,
 }
 
 impl<'a> Visitor<'a> {
-    pub(super) fn new(current_file_path: &PathBuf, resolver: &'a Resolver) -> Self {
+    pub(super) fn new(
+        current_file_path: &PathBuf,
+        source_code: &'a str,
+        resolver: &'a Resolver,
+    ) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(
+                source_code
+                    .match_indices('\n')
+                    .map(|(idx, _)| idx as u32 + 1),
+            )
+            .collect();
+
         Self {
             imports: Vec::new(),
+            diagnostics: Vec::new(),
+            current_file_path: current_file_path.clone(),
             current_file_dir: current_file_path
                 .parent()
                 .unwrap_or(Path::new(""))
                 .to_path_buf(),
+            source_code,
+            line_starts,
             resolver,
         }
     }
 
-    fn add_import<I>(&mut self, specifier: &str, identifiers: Option<I>)
-    where
+    pub(super) fn location(&self, span: Span) -> SourceLocation {
+        let line_idx = self
+            .line_starts
+            .partition_point(|&line_start| line_start <= span.start)
+            - 1;
+        let line_start = self.line_starts[line_idx] as usize;
+        let column = self
+            .source_code
+            .get(line_start..span.start as usize)
+            .map_or(0, |prefix| prefix.chars().count());
+
+        SourceLocation {
+            start: span.start,
+            end: span.end,
+            line: line_idx as u32 + 1,
+            column: column as u32 + 1,
+        }
+    }
+
+    fn add_import<I>(
+        &mut self,
+        specifier: &str,
+        kind: EdgeKind,
+        span: Span,
+        symbols: Vec<String>,
+        identifiers: Option<I>,
+    ) where
         I: IntoIterator<Item = &'a str> + std::fmt::Debug,
     {
         let context = self.current_file_dir.clone();
+        let location = self.location(span);
+        let (request, suffix) = split_suffix(specifier);
+        let kind = match suffix.and_then(suffix_kind) {
+            Some(suffix_kind) if kind != EdgeKind::TypeOnly => suffix_kind,
+            _ => kind,
+        };
+        let suffix = suffix.map(str::to_string);
 
-        if let Ok(resolution) = self.resolver.resolve(context, specifier) {
-            let path = resolution.full_path().to_path_buf();
-
-            if let Some(node) = Node::from_path(path.clone()) {
-                self.imports.push(node);
+        let resolution = match self.resolver.resolve(context, request) {
+            Ok(resolution) => resolution,
+            // Node.js built-in modules, like `fs` or `node:fs`, are not files.
+            Err(ResolveError::Builtin { .. }) => return,
+            Err(e) => {
+                self.diagnostics.push(Diagnostic::unresolved_import(
+                    self.current_file_path.clone(),
+                    location,
+                    specifier,
+                    e.to_string(),
+                ));
+                return;
             }
+        };
+        let path = resolution.full_path().to_path_buf();
 
-            if let Some(identifiers) = identifiers
-                && !(specifier.starts_with(".") || specifier.starts_with("/"))
-                && !path.to_string_lossy().contains("node_modules")
+        self.imports.push(Import {
+            path: path.clone(),
+            kind,
+            symbols: symbols.clone(),
+            location,
+            suffix,
+        });
+
+        if let Some(identifiers) = identifiers
+            && !(specifier.starts_with(".") || specifier.starts_with("/"))
+            && !path.to_string_lossy().contains("node_modules")
+        {
+            if self
+                .fallback_import_with_sourcemap(
+                    &path,
+                    kind,
+                    location,
+                    &identifiers.into_iter().collect(),
+                    &symbols,
+                )
+                .is_err()
             {
-                if self
-                    .fallback_import_with_sourcemap(&path, &identifiers.into_iter().collect())
-                    .is_err()
-                {
-                    tracing::warn!(
-                        "Failed to fallback imports with sourcemap for path: {:?}",
-                        path
-                    );
-                }
+                tracing::warn!(
+                    "Failed to fallback imports with sourcemap for path: {:?}",
+                    path
+                );
             }
         }
     }
@@ -57,14 +145,17 @@ impl<'a> Visitor<'a> {
     fn fallback_import_with_sourcemap(
         &mut self,
         path: &PathBuf,
+        kind: EdgeKind,
+        location: SourceLocation,
         identifiers: &Vec<&str>,
+        symbols: &[String],
     ) -> Result<(), ()> {
         let smap = self
             .get_external_sourcemap(path)
             .or_else(|| self.get_inline_sourcemap(path))
             .ok_or(())?;
 
-        for identifier in identifiers {
+        for (idx, identifier) in identifiers.iter().enumerate() {
             for token in smap.tokens() {
                 if let Some(name) = token.get_name() {
                     if name == *identifier {
@@ -81,9 +172,13 @@ impl<'a> Visitor<'a> {
                                     .unwrap_or(import_path.clone())
                             };
 
-                            if let Some(node) = Node::from_path(absolute_import_path) {
-                                self.imports.push(node);
-                            }
+                            self.imports.push(Import {
+                                path: absolute_import_path,
+                                kind,
+                                symbols: symbols.get(idx).cloned().into_iter().collect(),
+                                location,
+                                suffix: None,
+                            });
                         }
 
                         break;
@@ -125,10 +220,77 @@ impl<'a> Visitor<'a> {
     }
 }
 
+/// Splits `./x.svg?raw` into `./x.svg` and `?raw`, and `./x#hash` into `./x` and `#hash`.
+/// A leading `#` is a subpath import (`#internal/x`), not a fragment.
+pub(super) fn split_suffix(specifier: &str) -> (&str, Option<&str>) {
+    let idx = specifier
+        .find('?')
+        .or_else(|| specifier.get(1..)?.find('#').map(|idx| idx + 1));
+    match idx {
+        Some(idx) => (&specifier[..idx], Some(&specifier[idx..])),
+        None => (specifier, None),
+    }
+}
+
+/// Edge kind of bundler queries that change what is imported, as in Vite.
+fn suffix_kind(suffix: &str) -> Option<EdgeKind> {
+    let query = suffix.strip_prefix('?')?;
+    let keys = query
+        .split('&')
+        .map(|param| param.split_once('=').map_or(param, |(key, _)| key))
+        .collect::<Vec<_>>();
+    if keys
+        .iter()
+        .any(|key| matches!(*key, "worker" | "sharedworker"))
+    {
+        Some(EdgeKind::Worker)
+    } else if keys
+        .iter()
+        .any(|key| matches!(*key, "raw" | "url" | "inline"))
+    {
+        Some(EdgeKind::Asset)
+    } else {
+        None
+    }
+}
+
 impl<'a> Visit<'a> for Visitor<'a> {
     fn visit_import_declaration(&mut self, decl: &ImportDeclaration<'a>) {
+        let kind = match &decl.specifiers {
+            _ if decl.import_kind.is_type() => EdgeKind::TypeOnly,
+            None => EdgeKind::SideEffect,
+            Some(specifiers)
+                if !specifiers.is_empty()
+                    && specifiers.iter().all(|spec| match spec {
+                        ImportDeclarationSpecifier::ImportSpecifier(named) => {
+                            named.import_kind.is_type()
+                        }
+                        _ => false,
+                    }) =>
+            {
+                EdgeKind::TypeOnly
+            }
+            Some(_) => EdgeKind::Static,
+        };
+
+        let symbols = decl.specifiers.as_ref().map_or(vec![], |specifiers| {
+            specifiers
+                .iter()
+                .map(|spec| match spec {
+                    ImportDeclarationSpecifier::ImportDefaultSpecifier(_) => "default".to_string(),
+                    ImportDeclarationSpecifier::ImportNamespaceSpecifier(_) => "*".to_string(),
+                    ImportDeclarationSpecifier::ImportSpecifier(named) => {
+                        named.imported.name().to_string()
+                    }
+                })
+                .collect()
+        });
+
         self.add_import(
             decl.source.value.as_str(),
+            kind,
+            decl.span,
+            symbols,
             decl.specifiers.as_ref().map(|specifiers| {
                 specifiers.iter().map(|spec| match spec {
                     ImportDeclarationSpecifier::ImportDefaultSpecifier(default) => {
@@ -144,8 +306,17 @@ impl<'a> Visit<'a> for Visitor<'a> {
     }
 
     fn visit_export_all_declaration(&mut self, decl: &ExportAllDeclaration<'a>) {
+        let kind = if decl.export_kind.is_type() {
+            EdgeKind::TypeOnly
+        } else {
+            EdgeKind::ReExport
+        };
+
         self.add_import(
             decl.source.value.as_str(),
+            kind,
+            decl.span,
+            vec!["*".to_string()],
             Some(decl.exported.as_ref().map_or(vec![], |e| match e {
                 ModuleExportName::IdentifierName(identifier_name) => {
                     vec![identifier_name.name.as_str()]
@@ -162,24 +333,60 @@ impl<'a> Visit<'a> for Visitor<'a> {
 
     fn visit_export_named_declaration(&mut self, decl: &ExportNamedDeclaration<'a>) {
         if let Some(source) = &decl.source {
-            self.add_import(source.value.as_str(), None::<Vec<&str>>);
+            let kind = if decl.export_kind.is_type()
+                || (!decl.specifiers.is_empty()
+                    && decl
+                        .specifiers
+                        .iter()
+                        .all(|spec| spec.export_kind.is_type()))
+            {
+                EdgeKind::TypeOnly
+            } else {
+                EdgeKind::ReExport
+            };
+            let symbols = decl
+                .specifiers
+                .iter()
+                .map(|spec| spec.local.name().to_string())
+                .collect();
+            self.add_import(
+                source.value.as_str(),
+                kind,
+                decl.span,
+                symbols,
+                None::<Vec<&str>>,
+            );
         }
+
+        walk::walk_export_named_declaration(self, decl);
     }
 
-    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
-        if let Expression::ImportExpression(_) = &expr.callee {
-            if let Some(first_arg) = expr.arguments.first() {
-                if let Argument::StringLiteral(str_lit) = first_arg {
-                    self.add_import(str_lit.value.as_str(), None::<Vec<&str>>);
-                }
-            }
+    fn visit_import_expression(&mut self, expr: &ImportExpression<'a>) {
+        if let Expression::StringLiteral(str_lit) = &expr.source {
+            self.add_import(
+                str_lit.value.as_str(),
+                EdgeKind::Dynamic,
+                expr.span,
+                vec![],
+                None::<Vec<&str>>,
+            );
         }
 
+        walk::walk_import_expression(self, expr);
+    }
+
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
         if let Expression::Identifier(ident) = &expr.callee {
             if ident.name == "require" {
                 if let Some(first_arg) = expr.arguments.first() {
                     if let Argument::StringLiteral(str_lit) = first_arg {
-                        self.add_import(str_lit.value.as_str(), None::<Vec<&str>>);
+                        self.add_import(
+                            str_lit.value.as_str(),
+                            EdgeKind::Require,
+                            expr.span,
+                            vec![],
+                            None::<Vec<&str>>,
+                        );
                     }
                 }
             }
@@ -198,7 +405,19 @@ impl<'a> Visit<'a> for Visitor<'a> {
                     if ident.name == "require" {
                         if let Some(first_arg) = call_expr.arguments.first() {
                             if let Argument::StringLiteral(str_lit) = first_arg {
-                                self.add_import(str_lit.value.as_str(), None::<Vec<&str>>);
+                                self.add_import(
+                                    str_lit.value.as_str(),
+                                    EdgeKind::Require,
+                                    call_expr.span,
+                                    vec![static_expr.property.name.to_string()],
+                                    None::<Vec<&str>>,
+                                );
+                                // Visiting the call would record the import again, without
+                                // its symbol.
+                                for arg in &call_expr.arguments {
+                                    self.visit_argument(arg);
+                                }
+                                return;
                             }
                         }
                     }
@@ -220,3 +439,58 @@ impl<'a> Visit<'a> for Visitor<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_resolver::ResolveOptions;
+    use oxc_span::SourceType;
+    use test_log::test;
+
+    #[test]
+    fn it_records_a_required_member_once() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let file_path = root.join("index.js");
+        std::fs::write(root.join("config.js"), "module.exports.foo = 1;").unwrap();
+        let source = "const foo = require('./config').foo;";
+
+        let allocator = Allocator::default();
+        let program = Parser::new(&allocator, source, SourceType::cjs())
+            .parse()
+            .program;
+        let resolver = Resolver::new(ResolveOptions {
+            extensions: vec![".js".into()],
+            ..ResolveOptions::default()
+        });
+        let mut visitor = Visitor::new(&file_path, source, &resolver);
+        visitor.visit_program(&program);
+
+        assert_eq!(visitor.imports.len(), 1);
+        assert_eq!(visitor.imports[0].path, root.join("config.js"));
+        assert_eq!(visitor.imports[0].kind, EdgeKind::Require);
+        assert_eq!(visitor.imports[0].symbols, vec!["foo"]);
+    }
+
+    #[test]
+    fn it_skips_builtin_modules() {
+        let file_path = PathBuf::from("/project/index.ts");
+        let source = "import fs from 'fs';\nimport { join } from 'node:path';";
+
+        let allocator = Allocator::default();
+        let program = Parser::new(&allocator, source, SourceType::ts())
+            .parse()
+            .program;
+        let resolver = Resolver::new(ResolveOptions {
+            builtin_modules: true,
+            ..ResolveOptions::default()
+        });
+        let mut visitor = Visitor::new(&file_path, source, &resolver);
+        visitor.visit_program(&program);
+
+        assert!(visitor.imports.is_empty());
+        assert!(visitor.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+use crate::graph::{Graph, NodeId, NodeKind};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+/// Directories are keyed by their project-relative path with `/` separators, the project
+/// root being `""`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CompoundNodeId {
+    Directory(String),
+    File(NodeId),
+}
+
+#[derive(Debug, Clone)]
+pub struct CompoundNode {
+    pub id: CompoundNodeId,
+    /// Key of the enclosing directory, `None` for the project root.
+    pub parent: Option<String>,
+    pub label: String,
+    /// Whether this directory stands for everything below it.
+    pub collapsed: bool,
+    /// Number of files in this directory and its subdirectories.
+    pub file_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundEdge {
+    pub from: CompoundNodeId,
+    pub to: CompoundNodeId,
+    /// Number of file-level edges this edge stands for.
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompoundView {
+    pub nodes: Vec<CompoundNode>,
+    pub edges: Vec<CompoundEdge>,
+}
+
+/// The directory hierarchy of a [`Graph`]: directories are parent nodes and files are leaves.
+/// Collapsed directories hide their content and take over its edges.
+#[derive(Debug, Clone)]
+pub struct CompoundGraph {
+    root_label: String,
+    /// Files directly in each directory, including directories without files.
+    directories: BTreeMap<String, Vec<(NodeId, String)>>,
+    file_directory: HashMap<NodeId, String>,
+    edges: Vec<(NodeId, NodeId)>,
+    collapsed: HashSet<String>,
+}
+
+impl CompoundGraph {
+    pub fn new(graph: &Graph, project_root: &Path) -> Self {
+        let mut directories: BTreeMap<String, Vec<(NodeId, String)>> =
+            BTreeMap::from([(String::new(), Vec::new())]);
+        let mut file_directory = HashMap::new();
+
+        for node in graph.iter_nodes() {
+            let relative_path = node
+                .file_path
+                .strip_prefix(project_root)
+                .unwrap_or(&node.file_path);
+            // External packages and the files imported from them are not part of the project.
+            if node.kind == NodeKind::ExternalPackage
+                || relative_path
+                    .components()
+                    .any(|component| component.as_os_str() == "node_modules")
+            {
+                continue;
+            }
+            let directory = relative_path
+                .parent()
+                .map(|parent| {
+                    parent
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                })
+                .unwrap_or_default();
+
+            for ancestor in ancestors(&directory) {
+                directories.entry(ancestor.to_string()).or_default();
+            }
+            directories
+                .entry(directory.clone())
+                .or_default()
+                .push((node.id, node.label.clone()));
+            file_directory.insert(node.id, directory);
+        }
+        for files in directories.values_mut() {
+            files.sort_unstable();
+        }
+
+        let mut edges = graph
+            .iter_edges()
+            .map(|edge| (edge.from, edge.to))
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+
+        Self {
+            root_label: project_root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            directories,
+            file_directory,
+            edges,
+            collapsed: HashSet::new(),
+        }
+    }
+
+    /// Returns `false` if `directory` is not part of the hierarchy.
+    pub fn collapse(&mut self, directory: &str) -> bool {
+        if !self.directories.contains_key(directory) {
+            return false;
+        }
+        self.collapsed.insert(directory.to_string());
+        true
+    }
+
+    /// Returns `false` if `directory` was not collapsed.
+    pub fn expand(&mut self, directory: &str) -> bool {
+        self.collapsed.remove(directory)
+    }
+
+    /// Collapses every directory `depth` levels below the root, e.g. `1` shows only the
+    /// top-level directories and files.
+    pub fn collapse_to_depth(&mut self, depth: usize) {
+        self.collapsed = self
+            .directories
+            .keys()
+            .filter(|directory| !directory.is_empty() && directory.split('/').count() == depth)
+            .cloned()
+            .collect();
+    }
+
+    pub fn expand_all(&mut self) {
+        self.collapsed.clear();
+    }
+
+    pub fn view(&self) -> CompoundView {
+        let file_counts = self.file_counts();
+        let mut nodes = Vec::new();
+
+        for (directory, files) in &self.directories {
+            if self.hidden(directory) {
+                continue;
+            }
+            let collapsed = self.collapsed.contains(directory);
+            nodes.push(CompoundNode {
+                id: CompoundNodeId::Directory(directory.clone()),
+                parent: parent(directory).map(str::to_string),
+                label: match directory.rsplit_once('/') {
+                    Some((_, name)) => name.to_string(),
+                    None if directory.is_empty() => self.root_label.clone(),
+                    None => directory.clone(),
+                },
+                collapsed,
+                file_count: file_counts[directory.as_str()],
+            });
+            if !collapsed {
+                nodes.extend(files.iter().map(|(id, label)| CompoundNode {
+                    id: CompoundNodeId::File(*id),
+                    parent: Some(directory.clone()),
+                    label: label.clone(),
+                    collapsed: false,
+                    file_count: 1,
+                }));
+            }
+        }
+
+        let mut counts: BTreeMap<(CompoundNodeId, CompoundNodeId), usize> = BTreeMap::new();
+        for &(from, to) in &self.edges {
+            let (Some(from), Some(to)) = (self.visible_node(from), self.visible_node(to)) else {
+                continue;
+            };
+            if from != to {
+                *counts.entry((from, to)).or_default() += 1;
+            }
+        }
+
+        CompoundView {
+            nodes,
+            edges: counts
+                .into_iter()
+                .map(|((from, to), count)| CompoundEdge { from, to, count })
+                .collect(),
+        }
+    }
+
+    /// The file itself, or its outermost collapsed directory.
+    fn visible_node(&self, id: NodeId) -> Option<CompoundNodeId> {
+        let directory = self.file_directory.get(&id)?;
+        Some(
+            ancestors(directory)
+                .chain(std::iter::once(directory.as_str()))
+                .find(|ancestor| self.collapsed.contains(*ancestor))
+                .map_or(CompoundNodeId::File(id), |ancestor| {
+                    CompoundNodeId::Directory(ancestor.to_string())
+                }),
+        )
+    }
+
+    /// Whether `directory` is inside a collapsed directory.
+    fn hidden(&self, directory: &str) -> bool {
+        ancestors(directory).any(|ancestor| self.collapsed.contains(ancestor))
+    }
+
+    fn file_counts(&self) -> HashMap<&str, usize> {
+        let mut counts = HashMap::new();
+        for (directory, files) in &self.directories {
+            for ancestor in ancestors(directory).chain(std::iter::once(directory.as_str())) {
+                *counts.entry(ancestor).or_default() += files.len();
+            }
+        }
+        counts
+    }
+}
+
+fn parent(directory: &str) -> Option<&str> {
+    if directory.is_empty() {
+        None
+    } else {
+        Some(directory.rsplit_once('/').map_or("", |(parent, _)| parent))
+    }
+}
+
+/// Strict ancestors of `directory`, from the project root down.
+fn ancestors(directory: &str) -> impl Iterator<Item = &str> {
+    let mut ancestors = Vec::new();
+    let mut current = directory;
+    while let Some(parent) = parent(current) {
+        ancestors.push(parent);
+        current = parent;
+    }
+    ancestors.into_iter().rev()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, EdgeKind, Node, test_node};
+    use test_log::test;
+
+    #[test]
+    fn it_collapses_and_expands_directories() {
+        let root = Path::new("/project");
+        let id = |path: &str| Node::id_for(root, &root.join(path));
+        let mut graph = Graph::new();
+        for path in ["index.ts", "src/a/one.ts", "src/a/two.ts", "src/b/three.ts"] {
+            graph.add_node(test_node(id(path), root.join(path)));
+        }
+        for (from, to) in [
+            ("index.ts", "src/a/one.ts"),
+            ("src/a/one.ts", "src/a/two.ts"),
+            ("src/a/one.ts", "src/b/three.ts"),
+            ("src/a/two.ts", "src/b/three.ts"),
+        ] {
+            graph.add_edge(Edge::new(id(from), id(to), EdgeKind::Static));
+        }
+
+        let mut compound = CompoundGraph::new(&graph, root);
+        let view = compound.view();
+        assert_eq!(view.nodes.len(), 4 + 4);
+        assert_eq!(view.edges.len(), 4);
+        assert_eq!(view.nodes[0].label, "project");
+        assert_eq!(view.nodes[0].file_count, 4);
+
+        assert!(compound.collapse("src/a"));
+        let view = compound.view();
+        assert_eq!(view.nodes.len(), 4 + 2);
+        assert_eq!(
+            view.edges,
+            vec![
+                CompoundEdge {
+                    from: CompoundNodeId::Directory("src/a".to_string()),
+                    to: CompoundNodeId::File(id("src/b/three.ts")),
+                    count: 2,
+                },
+                CompoundEdge {
+                    from: CompoundNodeId::File(id("index.ts")),
+                    to: CompoundNodeId::Directory("src/a".to_string()),
+                    count: 1,
+                },
+            ]
+        );
+
+        compound.collapse_to_depth(1);
+        let view = compound.view();
+        assert_eq!(view.nodes.len(), 3);
+        assert_eq!(view.edges.len(), 1);
+        assert_eq!(view.edges[0].count, 1);
+
+        assert!(compound.expand("src"));
+        assert!(!compound.collapse("missing"));
+        assert_eq!(compound.view().nodes.len(), 8);
+    }
+
+    #[test]
+    fn it_leaves_external_packages_out() {
+        let root = Path::new("/project");
+        let id = |path: &str| Node::id_for(root, &root.join(path));
+        let mut graph = Graph::new();
+        graph.add_node(test_node(id("src/index.ts"), root.join("src/index.ts")));
+        graph.add_node(Node {
+            kind: NodeKind::ExternalPackage,
+            ..test_node(id("node_modules/react"), root.join("node_modules/react"))
+        });
+        graph.add_node(test_node(
+            id("node_modules/react/index.css"),
+            root.join("node_modules/react/index.css"),
+        ));
+        graph.add_edge(Edge::new(
+            id("src/index.ts"),
+            id("node_modules/react"),
+            EdgeKind::Static,
+        ));
+
+        let view = CompoundGraph::new(&graph, root).view();
+        assert_eq!(
+            view.nodes
+                .iter()
+                .map(|node| node.id.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                CompoundNodeId::Directory(String::new()),
+                CompoundNodeId::Directory("src".to_string()),
+                CompoundNodeId::File(id("src/index.ts")),
+            ]
+        );
+        assert!(view.edges.is_empty());
+    }
+}
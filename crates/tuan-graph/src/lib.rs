@@ -1,3 +1,13 @@
 pub mod graph;
 pub mod graph_builders;
 pub mod cluster;
+pub mod compare;
+pub mod cycles;
+pub mod diagnostics;
+pub mod export;
+pub mod hierarchy;
+pub mod metrics;
+pub mod packages;
+pub mod query;
+pub mod rules;
+pub mod watch;
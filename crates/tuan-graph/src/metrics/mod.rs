@@ -0,0 +1,264 @@
+use crate::{
+    cycles::strongly_connected_components,
+    graph::{Graph, NodeId},
+};
+use rayon::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+const PAGE_RANK_DAMPING: f64 = 0.85;
+const PAGE_RANK_TOLERANCE: f64 = 1e-9;
+const PAGE_RANK_MAX_ITERATIONS: usize = 100;
+/// Above this many files, betweenness is estimated from this many evenly spread sources.
+const BETWEENNESS_SAMPLES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NodeMetrics {
+    /// Number of files importing this one (afferent coupling, Ca).
+    pub fan_in: usize,
+    /// Number of files imported by this one (efferent coupling, Ce).
+    pub fan_out: usize,
+    /// Sums to 1 over the graph. Heavily imported files rank higher.
+    pub page_rank: f64,
+    /// Share of shortest import chains going through this file, between 0 and 1.
+    pub betweenness: f64,
+    /// Length of the longest import chain starting at this file. Files of a cycle share
+    /// the same depth.
+    pub depth: usize,
+    /// `Ce / (Ca + Ce)`: 0 for files that only get imported, 1 for files that only import.
+    pub instability: f64,
+}
+
+impl Graph {
+    pub fn metrics(&self) -> HashMap<NodeId, NodeMetrics> {
+        measure_time::info_time!("Computing graph metrics");
+
+        let mut ids = self.nodes.keys().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+        let indexes = ids
+            .iter()
+            .enumerate()
+            .map(|(idx, &id)| (id, idx))
+            .collect::<HashMap<_, _>>();
+
+        let mut outgoing = vec![Vec::new(); ids.len()];
+        let mut incoming = vec![Vec::new(); ids.len()];
+        for edge in &self.edges {
+            let (Some(&from), Some(&to)) = (indexes.get(&edge.from), indexes.get(&edge.to)) else {
+                continue;
+            };
+            if from != to {
+                outgoing[from].push(to);
+                incoming[to].push(from);
+            }
+        }
+        for neighbors in outgoing.iter_mut().chain(incoming.iter_mut()) {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+
+        let page_rank = page_rank(&outgoing);
+        let betweenness = betweenness(&outgoing);
+        let depth = self.depths(&ids, &indexes);
+
+        ids.iter()
+            .enumerate()
+            .map(|(idx, &id)| {
+                let fan_in = incoming[idx].len();
+                let fan_out = outgoing[idx].len();
+                let instability = if fan_in + fan_out == 0 {
+                    0.0
+                } else {
+                    fan_out as f64 / (fan_in + fan_out) as f64
+                };
+                let metrics = NodeMetrics {
+                    fan_in,
+                    fan_out,
+                    page_rank: page_rank[idx],
+                    betweenness: betweenness[idx],
+                    depth: depth[idx],
+                    instability,
+                };
+                (id, metrics)
+            })
+            .collect()
+    }
+
+    /// Longest chain in the graph of strongly connected components.
+    fn depths(&self, ids: &[NodeId], indexes: &HashMap<NodeId, usize>) -> Vec<usize> {
+        let mut adjacency: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for edge in &self.edges {
+            adjacency.entry(edge.from).or_default().push(edge.to);
+        }
+
+        let components = strongly_connected_components(ids, &adjacency);
+        let mut component_of = HashMap::new();
+        for (component_idx, component) in components.iter().enumerate() {
+            for &id in component {
+                component_of.insert(id, component_idx);
+            }
+        }
+
+        // Tarjan emits a component after every component it leads to.
+        let mut component_depth = vec![0; components.len()];
+        for (component_idx, component) in components.iter().enumerate() {
+            component_depth[component_idx] = component
+                .iter()
+                .flat_map(|id| adjacency.get(id).into_iter().flatten())
+                .map(|next| component_of[next])
+                .filter(|&next_component| next_component != component_idx)
+                .map(|next_component| component_depth[next_component] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        let mut depths = vec![0; ids.len()];
+        for (&id, &component_idx) in &component_of {
+            depths[indexes[&id]] = component_depth[component_idx];
+        }
+        depths
+    }
+}
+
+fn page_rank(outgoing: &[Vec<usize>]) -> Vec<f64> {
+    let n = outgoing.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..PAGE_RANK_MAX_ITERATIONS {
+        // Files importing nothing spread their rank over every file.
+        let dangling = (0..n)
+            .filter(|&idx| outgoing[idx].is_empty())
+            .map(|idx| rank[idx])
+            .sum::<f64>();
+        let base = (1.0 - PAGE_RANK_DAMPING + PAGE_RANK_DAMPING * dangling) / n as f64;
+
+        let mut next = vec![base; n];
+        for (idx, neighbors) in outgoing.iter().enumerate() {
+            let share = PAGE_RANK_DAMPING * rank[idx] / neighbors.len().max(1) as f64;
+            for &neighbor in neighbors {
+                next[neighbor] += share;
+            }
+        }
+
+        let delta = rank
+            .iter()
+            .zip(&next)
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f64>();
+        rank = next;
+        if delta < PAGE_RANK_TOLERANCE {
+            break;
+        }
+    }
+    rank
+}
+
+/// Brandes' algorithm, normalized by the number of ordered pairs of other files.
+fn betweenness(outgoing: &[Vec<usize>]) -> Vec<f64> {
+    let n = outgoing.len();
+    if n < 3 {
+        return vec![0.0; n];
+    }
+
+    let step = n.div_ceil(BETWEENNESS_SAMPLES);
+    let sources = (0..n).step_by(step).collect::<Vec<_>>();
+    let scale = n as f64 / sources.len() as f64 / ((n - 1) * (n - 2)) as f64;
+
+    sources
+        .par_iter()
+        .map(|&source| single_source_dependencies(outgoing, source))
+        .reduce(
+            || vec![0.0; n],
+            |mut acc, dependencies| {
+                acc.iter_mut()
+                    .zip(dependencies)
+                    .for_each(|(total, dependency)| *total += dependency);
+                acc
+            },
+        )
+        .into_iter()
+        .map(|total| total * scale)
+        .collect()
+}
+
+fn single_source_dependencies(outgoing: &[Vec<usize>], source: usize) -> Vec<f64> {
+    let n = outgoing.len();
+    let mut stack = Vec::new();
+    let mut predecessors = vec![Vec::new(); n];
+    let mut paths = vec![0.0; n];
+    let mut distance = vec![usize::MAX; n];
+    paths[source] = 1.0;
+    distance[source] = 0;
+
+    let mut queue = VecDeque::from([source]);
+    while let Some(current) = queue.pop_front() {
+        stack.push(current);
+        for &next in &outgoing[current] {
+            if distance[next] == usize::MAX {
+                distance[next] = distance[current] + 1;
+                queue.push_back(next);
+            }
+            if distance[next] == distance[current] + 1 {
+                paths[next] += paths[current];
+                predecessors[next].push(current);
+            }
+        }
+    }
+
+    let mut dependencies = vec![0.0; n];
+    let mut result = vec![0.0; n];
+    while let Some(current) = stack.pop() {
+        for &previous in &predecessors[current] {
+            dependencies[previous] +=
+                paths[previous] / paths[current] * (1.0 + dependencies[current]);
+        }
+        if current != source {
+            result[current] = dependencies[current];
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Edge, EdgeKind, Graph, test_node};
+    use test_log::test;
+
+    /// 1 -> 2 -> 3 -> 4, 1 -> 5 -> 3, 4 <-> 6
+    fn graph() -> Graph {
+        let mut graph = Graph::new();
+        for id in 1..=6 {
+            graph.add_node(test_node(id, format!("/project/{id}.ts")));
+        }
+        for (from, to) in [(1, 2), (2, 3), (3, 4), (1, 5), (5, 3), (4, 6), (6, 4)] {
+            graph.add_edge(Edge::new(from, to, EdgeKind::Static));
+        }
+        graph
+    }
+
+    #[test]
+    fn it_computes_node_metrics() {
+        let metrics = graph().metrics();
+
+        assert_eq!(metrics[&1].fan_in, 0);
+        assert_eq!(metrics[&1].fan_out, 2);
+        assert_eq!(metrics[&1].instability, 1.0);
+        assert_eq!(metrics[&3].fan_in, 2);
+        assert_eq!(metrics[&3].instability, 1.0 / 3.0);
+
+        assert_eq!(metrics[&1].depth, 3);
+        assert_eq!(metrics[&3].depth, 1);
+        assert_eq!(metrics[&4].depth, 0);
+        assert_eq!(metrics[&6].depth, 0);
+
+        let total_rank = metrics.values().map(|m| m.page_rank).sum::<f64>();
+        assert!((total_rank - 1.0).abs() < 1e-6);
+        assert!(metrics[&4].page_rank > metrics[&2].page_rank);
+
+        // Every chain from {1, 2, 5} to {4, 6} goes through 3.
+        assert!(metrics[&3].betweenness > metrics[&2].betweenness);
+        assert_eq!(metrics[&1].betweenness, 0.0);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::graph::{Edge, Graph, NodeId, NodeKind};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone)]
+pub struct Package {
+    /// Index of the package in [`PackageGraph::packages`].
+    pub id: usize,
+    pub name: String,
+    /// Directory holding the `package.json`.
+    pub root: PathBuf,
+    pub members: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageEdge {
+    pub from: usize,
+    pub to: usize,
+    /// The file-level imports from `from` to `to`, for drilling down.
+    pub edges: Vec<Edge>,
+}
+
+impl PackageEdge {
+    pub fn count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PackageGraph {
+    pub packages: Vec<Package>,
+    pub edges: Vec<PackageEdge>,
+}
+
+impl PackageGraph {
+    /// Package the file `id` was assigned to.
+    pub fn package_of(&self, id: NodeId) -> Option<&Package> {
+        self.packages
+            .iter()
+            .find(|package| package.members.contains(&id))
+    }
+}
+
+impl Graph {
+    /// Groups source files and stylesheets by their nearest enclosing package, given as
+    /// `(name, root)` pairs. Files outside of every package are left out, and so are imports
+    /// within a package. External packages and assets are not members of any package.
+    pub fn package_graph(&self, packages: &[(String, PathBuf)]) -> PackageGraph {
+        let mut packages = packages.to_vec();
+        packages.sort_by(|a, b| a.1.cmp(&b.1));
+        packages.dedup_by(|a, b| a.1 == b.1);
+        let roots = packages
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, root))| (root.as_path(), idx))
+            .collect::<HashMap<&Path, usize>>();
+
+        let mut package_of = HashMap::new();
+        let mut members = vec![Vec::new(); packages.len()];
+        for node in self.nodes.values() {
+            if !matches!(node.kind, NodeKind::Source | NodeKind::Stylesheet) {
+                continue;
+            }
+            if let Some(&idx) = node.file_path.ancestors().find_map(|dir| roots.get(dir)) {
+                package_of.insert(node.id, idx);
+                members[idx].push(node.id);
+            }
+        }
+
+        let mut package_edges: HashMap<(usize, usize), Vec<Edge>> = HashMap::new();
+        for edge in &self.edges {
+            if let (Some(&from), Some(&to)) = (package_of.get(&edge.from), package_of.get(&edge.to))
+                && from != to
+            {
+                package_edges
+                    .entry((from, to))
+                    .or_default()
+                    .push(edge.clone());
+            }
+        }
+
+        let mut edges = package_edges
+            .into_iter()
+            .map(|((from, to), mut edges)| {
+                edges.sort_by_key(|edge| {
+                    (
+                        edge.from,
+                        edge.to,
+                        edge.location.map(|location| location.start),
+                    )
+                });
+                PackageEdge { from, to, edges }
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|edge| (edge.from, edge.to));
+
+        PackageGraph {
+            packages: packages
+                .into_iter()
+                .zip(members)
+                .enumerate()
+                .map(|(id, ((name, root), mut members))| {
+                    members.sort_unstable();
+                    Package {
+                        id,
+                        name,
+                        root,
+                        members,
+                    }
+                })
+                .collect(),
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Edge, EdgeKind, Graph, Node, NodeKind, test_node};
+    use std::path::{Path, PathBuf};
+    use test_log::test;
+
+    #[test]
+    fn it_aggregates_files_into_packages() {
+        let root = Path::new("/repo");
+        let mut graph = Graph::new();
+        let id = |path: &str| Node::id_for(root, &root.join(path));
+        for path in [
+            "packages/app/src/main.ts",
+            "packages/app/src/view.ts",
+            "packages/app/nested/package/index.ts",
+            "packages/lib/index.ts",
+            "scripts/build.ts",
+        ] {
+            graph.add_node(test_node(id(path), root.join(path)));
+        }
+        for (from, to) in [
+            ("packages/app/src/main.ts", "packages/app/src/view.ts"),
+            ("packages/app/src/main.ts", "packages/lib/index.ts"),
+            ("packages/app/src/view.ts", "packages/lib/index.ts"),
+            (
+                "packages/app/src/view.ts",
+                "packages/app/nested/package/index.ts",
+            ),
+            ("scripts/build.ts", "packages/lib/index.ts"),
+        ] {
+            graph.add_edge(Edge::new(id(from), id(to), EdgeKind::Static));
+        }
+
+        let packages = [
+            ("@repo/lib".to_string(), root.join("packages/lib")),
+            ("@repo/app".to_string(), root.join("packages/app")),
+            (
+                "nested".to_string(),
+                root.join("packages/app/nested/package"),
+            ),
+        ];
+        let package_graph = graph.package_graph(&packages);
+
+        let names = package_graph
+            .packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["@repo/app", "nested", "@repo/lib"]);
+        assert_eq!(package_graph.packages[0].members.len(), 2);
+        assert!(package_graph.package_of(id("scripts/build.ts")).is_none());
+        assert_eq!(
+            package_graph
+                .package_of(id("packages/app/nested/package/index.ts"))
+                .map(|package| package.root.clone()),
+            Some(PathBuf::from("/repo/packages/app/nested/package"))
+        );
+
+        assert_eq!(package_graph.edges.len(), 2);
+        assert_eq!(
+            (package_graph.edges[0].from, package_graph.edges[0].to),
+            (0, 1)
+        );
+        assert_eq!(package_graph.edges[1].count(), 2);
+    }
+
+    #[test]
+    fn it_leaves_external_packages_and_assets_out() {
+        let root = Path::new("/repo");
+        let mut graph = Graph::new();
+        let id = |path: &str| Node::id_for(root, &root.join(path));
+        for (path, kind) in [
+            ("src/main.ts", NodeKind::Source),
+            ("src/main.css", NodeKind::Stylesheet),
+            ("src/logo.svg", NodeKind::Asset),
+            ("node_modules/react", NodeKind::ExternalPackage),
+        ] {
+            graph.add_node(Node {
+                kind,
+                ..test_node(id(path), root.join(path))
+            });
+            graph.add_edge(Edge::new(id("src/main.ts"), id(path), EdgeKind::Static));
+        }
+
+        let package_graph = graph.package_graph(&[(".".to_string(), root.to_path_buf())]);
+        let mut members = vec![id("src/main.ts"), id("src/main.css")];
+        members.sort_unstable();
+        assert_eq!(package_graph.packages[0].members, members);
+        assert!(package_graph.package_of(id("node_modules/react")).is_none());
+        assert!(package_graph.package_of(id("src/logo.svg")).is_none());
+        assert!(package_graph.edges.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::graph::{Graph, NodeId};
+use std::collections::{HashMap, HashSet, VecDeque, hash_map::Entry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+impl Graph {
+    /// Files imported by `id`. `max_depth` of `Some(1)` only returns direct imports, `None`
+    /// follows imports transitively. Nodes are ordered by distance, then by id.
+    pub fn dependencies(&self, id: NodeId, max_depth: Option<usize>) -> Vec<NodeId> {
+        self.walk(id, max_depth, Direction::Outgoing)
+    }
+
+    /// Files importing `id`, see [`Graph::dependencies`].
+    pub fn dependents(&self, id: NodeId, max_depth: Option<usize>) -> Vec<NodeId> {
+        self.walk(id, max_depth, Direction::Incoming)
+    }
+
+    /// Whether `to` is imported by `from`, directly or transitively.
+    pub fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        from == to || self.shortest_path(from, to).is_some()
+    }
+
+    /// Shortest chain of imports leading from `from` to `to`, both included.
+    pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        if !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
+            return None;
+        }
+
+        let adjacency = self.adjacency(Direction::Outgoing);
+        let mut previous = HashMap::from([(from, from)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                let mut node = to;
+                while node != from {
+                    node = previous[&node];
+                    path.push(node);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in adjacency.get(&current).into_iter().flatten() {
+                if let Entry::Vacant(entry) = previous.entry(next) {
+                    entry.insert(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Every chain of imports from `from` to `to` going through at most `max_length` edges and
+    /// visiting each file once. The number of paths grows quickly, so keep `max_length` small.
+    pub fn simple_paths(&self, from: NodeId, to: NodeId, max_length: usize) -> Vec<Vec<NodeId>> {
+        let mut paths = Vec::new();
+        if !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
+            return paths;
+        }
+
+        let adjacency = self.adjacency(Direction::Outgoing);
+        collect_simple_paths(
+            &adjacency,
+            to,
+            max_length,
+            &mut vec![from],
+            &mut HashSet::from([from]),
+            &mut paths,
+        );
+        paths
+    }
+
+    /// `changed` files and every file importing one of them, directly or transitively, sorted
+    /// by id.
+    pub fn affected_by(&self, changed: &[NodeId]) -> Vec<NodeId> {
+        let adjacency = self.adjacency(Direction::Incoming);
+        let mut affected = changed
+            .iter()
+            .copied()
+            .filter(|id| self.nodes.contains_key(id))
+            .collect::<HashSet<_>>();
+        let mut queue = affected.iter().copied().collect::<VecDeque<_>>();
+        while let Some(current) = queue.pop_front() {
+            for &next in adjacency.get(&current).into_iter().flatten() {
+                if affected.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut affected = affected.into_iter().collect::<Vec<_>>();
+        affected.sort_unstable();
+        affected
+    }
+
+    /// Flags every file that no entry point leads to, following every kind of import, and
+    /// returns them sorted by id.
+    pub fn mark_unreachable(&mut self, entry_points: &[NodeId]) -> Vec<NodeId> {
+        let adjacency = self.adjacency(Direction::Outgoing);
+        let mut reachable = entry_points.iter().copied().collect::<HashSet<_>>();
+        let mut queue = reachable.iter().copied().collect::<VecDeque<_>>();
+        while let Some(current) = queue.pop_front() {
+            for &next in adjacency.get(&current).into_iter().flatten() {
+                if reachable.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for node in self.nodes.values_mut() {
+            node.dead = !reachable.contains(&node.id);
+        }
+        self.dead_files()
+    }
+
+    /// Files flagged by the last [`Graph::mark_unreachable`], sorted by id.
+    pub fn dead_files(&self) -> Vec<NodeId> {
+        let mut dead = self
+            .nodes
+            .values()
+            .filter(|node| node.dead)
+            .map(|node| node.id)
+            .collect::<Vec<_>>();
+        dead.sort_unstable();
+        dead
+    }
+
+    fn walk(&self, id: NodeId, max_depth: Option<usize>, direction: Direction) -> Vec<NodeId> {
+        if !self.nodes.contains_key(&id) {
+            return Vec::new();
+        }
+
+        let adjacency = self.adjacency(direction);
+        let mut visited = HashSet::from([id]);
+        let mut result = Vec::new();
+        let mut frontier = vec![id];
+        let mut depth = 0;
+        while !frontier.is_empty() && max_depth.is_none_or(|max_depth| depth < max_depth) {
+            let mut next_frontier = frontier
+                .iter()
+                .flat_map(|node| adjacency.get(node).into_iter().flatten().copied())
+                .filter(|&next| visited.insert(next))
+                .collect::<Vec<_>>();
+            next_frontier.sort_unstable();
+            result.extend_from_slice(&next_frontier);
+            frontier = next_frontier;
+            depth += 1;
+        }
+
+        result
+    }
+
+    /// Sorted and deduplicated neighbors of every node, ignoring edge kinds.
+    fn adjacency(&self, direction: Direction) -> HashMap<NodeId, Vec<NodeId>> {
+        let mut adjacency: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for edge in &self.edges {
+            let (from, to) = match direction {
+                Direction::Outgoing => (edge.from, edge.to),
+                Direction::Incoming => (edge.to, edge.from),
+            };
+            adjacency.entry(from).or_default().push(to);
+        }
+        for neighbors in adjacency.values_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        adjacency
+    }
+}
+
+fn collect_simple_paths(
+    adjacency: &HashMap<NodeId, Vec<NodeId>>,
+    to: NodeId,
+    max_length: usize,
+    path: &mut Vec<NodeId>,
+    on_path: &mut HashSet<NodeId>,
+    paths: &mut Vec<Vec<NodeId>>,
+) {
+    let current = *path.last().unwrap();
+    if current == to {
+        paths.push(path.clone());
+        return;
+    }
+    if path.len() > max_length {
+        return;
+    }
+
+    for &next in adjacency.get(&current).into_iter().flatten() {
+        if on_path.insert(next) {
+            path.push(next);
+            collect_simple_paths(adjacency, to, max_length, path, on_path, paths);
+            path.pop();
+            on_path.remove(&next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Edge, EdgeKind, Graph, test_node};
+    use test_log::test;
+
+    /// 1 -> 2 -> 3 -> 4, 1 -> 3, 4 -> 2, 5 isolated
+    fn graph() -> Graph {
+        let mut graph = Graph::new();
+        for id in 1..=5 {
+            graph.add_node(test_node(id, format!("/project/{id}.ts")));
+        }
+        for (from, to) in [(1, 2), (2, 3), (3, 4), (1, 3), (4, 2)] {
+            graph.add_edge(Edge::new(from, to, EdgeKind::Static));
+        }
+        graph
+    }
+
+    #[test]
+    fn it_walks_dependencies_and_dependents() {
+        let graph = graph();
+
+        assert_eq!(graph.dependencies(1, Some(1)), vec![2, 3]);
+        assert_eq!(graph.dependencies(1, None), vec![2, 3, 4]);
+        assert_eq!(graph.dependents(3, Some(1)), vec![1, 2]);
+        assert_eq!(graph.dependents(3, None), vec![1, 2, 4]);
+        assert!(graph.dependents(1, None).is_empty());
+    }
+
+    #[test]
+    fn it_finds_paths() {
+        let graph = graph();
+
+        assert_eq!(graph.shortest_path(1, 4), Some(vec![1, 3, 4]));
+        assert_eq!(graph.shortest_path(4, 1), None);
+        assert!(graph.is_reachable(4, 3));
+        assert!(!graph.is_reachable(1, 5));
+        assert_eq!(graph.simple_paths(1, 4, 2), vec![vec![1, 3, 4]]);
+        assert_eq!(
+            graph.simple_paths(1, 4, 3),
+            vec![vec![1, 2, 3, 4], vec![1, 3, 4]]
+        );
+    }
+}
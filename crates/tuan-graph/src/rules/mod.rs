@@ -0,0 +1,318 @@
+use crate::graph::{Edge, Graph, Node};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Looked up in the project root when no other rules file is given.
+pub const DEFAULT_RULES_FILE: &str = "tuan.rules.json";
+
+/// A rules file looks like:
+///
+/// ```json
+/// {
+///   "rules": [
+///     { "name": "ui-not-server", "kind": "deny",
+///       "from": { "path": ["packages/ui/**"] }, "to": { "path": ["packages/server/**"] } },
+///     { "name": "core-internal", "kind": "deny",
+///       "from": { "path": ["**"], "pathNot": ["src/core/**"] },
+///       "to": { "path": ["src/core/internal/**"] } }
+///   ]
+/// }
+/// ```
+///
+/// Globs are matched against project-relative paths.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulesConfig {
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub kind: RuleKind,
+    pub from: Selector,
+    pub to: Selector,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleKind {
+    /// Files matching `from` may not import files matching `to`.
+    Deny,
+    /// Files matching `from` may only import files matching `to`. Files matched by several
+    /// allow rules may import the files allowed by any of them, and an import allowed by none
+    /// is reported once, under the first of them.
+    Allow,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Selector {
+    pub path: Vec<String>,
+    #[serde(default)]
+    pub path_not: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    Glob(globset::Error),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Io(e) => write!(f, "cannot read rules: {e}"),
+            RulesError::Parse(e) => write!(f, "invalid rules: {e}"),
+            RulesError::Glob(e) => write!(f, "invalid glob in rules: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl From<io::Error> for RulesError {
+    fn from(e: io::Error) -> Self {
+        RulesError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for RulesError {
+    fn from(e: serde_json::Error) -> Self {
+        RulesError::Parse(e)
+    }
+}
+
+impl From<globset::Error> for RulesError {
+    fn from(e: globset::Error) -> Self {
+        RulesError::Glob(e)
+    }
+}
+
+/// Rules with their globs compiled, ready to be checked against a graph.
+pub struct RuleSet {
+    rules: Vec<(Rule, CompiledSelector, CompiledSelector)>,
+}
+
+struct CompiledSelector {
+    path: GlobSet,
+    path_not: GlobSet,
+}
+
+impl CompiledSelector {
+    fn new(selector: &Selector) -> Result<Self, globset::Error> {
+        Ok(Self {
+            path: glob_set(&selector.path)?,
+            path_not: glob_set(&selector.path_not)?,
+        })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        self.path.is_match(path) && !self.path_not.is_match(path)
+    }
+}
+
+impl RuleSet {
+    pub fn new(config: RulesConfig) -> Result<Self, RulesError> {
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|rule| {
+                let from = CompiledSelector::new(&rule.from)?;
+                let to = CompiledSelector::new(&rule.to)?;
+                Ok((rule, from, to))
+            })
+            .collect::<Result<_, globset::Error>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, RulesError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::new(serde_json::from_str(&content)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub rule: String,
+    pub kind: RuleKind,
+    /// The offending import. Its `location` points at the import in the importing file.
+    pub edge: Edge,
+    pub from_path: PathBuf,
+    pub to_path: PathBuf,
+}
+
+impl Graph {
+    /// Imports breaking one of `rules`, ordered by importing file and location.
+    pub fn check_rules(&self, rules: &RuleSet, project_root: &Path) -> Vec<Violation> {
+        let relative_path = |node: &Node| {
+            node.file_path
+                .strip_prefix(project_root)
+                .unwrap_or(&node.file_path)
+                .to_path_buf()
+        };
+
+        let mut violations = Vec::new();
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (self.nodes.get(&edge.from), self.nodes.get(&edge.to))
+            else {
+                continue;
+            };
+            let (from_path, to_path) = (relative_path(from), relative_path(to));
+
+            let violation = |rule: &Rule| Violation {
+                rule: rule.name.clone(),
+                kind: rule.kind,
+                edge: edge.clone(),
+                from_path: from.file_path.clone(),
+                to_path: to.file_path.clone(),
+            };
+            let mut allow_rules = Vec::new();
+            for (rule, from_selector, to_selector) in &rules.rules {
+                if !from_selector.matches(&from_path) {
+                    continue;
+                }
+                match rule.kind {
+                    RuleKind::Deny if to_selector.matches(&to_path) => {
+                        violations.push(violation(rule))
+                    }
+                    RuleKind::Deny => {}
+                    RuleKind::Allow => allow_rules.push((rule, to_selector)),
+                }
+            }
+            if let Some((rule, _)) = allow_rules.first()
+                && !allow_rules
+                    .iter()
+                    .any(|(_, to_selector)| to_selector.matches(&to_path))
+            {
+                violations.push(violation(rule));
+            }
+        }
+
+        violations.sort_by(|a, b| {
+            (&a.from_path, a.edge.location.map(|l| l.start), &a.rule).cmp(&(
+                &b.from_path,
+                b.edge.location.map(|l| l.start),
+                &b.rule,
+            ))
+        });
+        violations
+    }
+}
+
+fn glob_set(globs: &[String]) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob)?);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{EdgeKind, SourceLocation, test_node};
+    use test_log::test;
+
+    fn graph(root: &Path) -> Graph {
+        let mut graph = Graph::new();
+        let paths = [
+            "packages/ui/button.ts",
+            "packages/server/db.ts",
+            "packages/shared/util.ts",
+            "src/core/index.ts",
+            "src/core/internal/secret.ts",
+            "src/app.ts",
+        ];
+        for path in paths {
+            let file_path = root.join(path);
+            graph.add_node(test_node(Node::id_for(root, &file_path), file_path));
+        }
+        let id = |path: &str| Node::id_for(root, &root.join(path));
+        for (from, to) in [
+            ("packages/ui/button.ts", "packages/server/db.ts"),
+            ("packages/ui/button.ts", "packages/shared/util.ts"),
+            ("src/core/index.ts", "src/core/internal/secret.ts"),
+            ("src/app.ts", "src/core/internal/secret.ts"),
+        ] {
+            graph.add_edge(Edge {
+                location: Some(SourceLocation {
+                    start: 0,
+                    end: 10,
+                    line: 1,
+                    column: 1,
+                }),
+                ..Edge::new(id(from), id(to), EdgeKind::Static)
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn it_reports_denied_and_not_allowed_imports() {
+        let root = Path::new("/project");
+        let rules = RuleSet::new(
+            serde_json::from_str(
+                r#"{ "rules": [
+                    { "name": "core-internal", "kind": "deny",
+                      "from": { "path": ["**"], "pathNot": ["src/core/**"] },
+                      "to": { "path": ["src/core/internal/**"] } },
+                    { "name": "ui-layer", "kind": "allow",
+                      "from": { "path": ["packages/ui/**"] },
+                      "to": { "path": ["packages/ui/**", "packages/shared/**"] } }
+                ] }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let violations = graph(root).check_rules(&rules, root);
+
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].rule, "ui-layer");
+        assert_eq!(violations[0].to_path, root.join("packages/server/db.ts"));
+        assert_eq!(violations[1].rule, "core-internal");
+        assert_eq!(violations[1].from_path, root.join("src/app.ts"));
+        assert_eq!(violations[1].edge.location.unwrap().line, 1);
+    }
+
+    #[test]
+    fn it_merges_overlapping_allow_rules() {
+        let root = Path::new("/project");
+        let rules = RuleSet::new(
+            serde_json::from_str(
+                r#"{ "rules": [
+                    { "name": "ui-layer", "kind": "allow",
+                      "from": { "path": ["packages/ui/**"] },
+                      "to": { "path": ["packages/ui/**", "packages/shared/**"] } },
+                    { "name": "packages-server", "kind": "allow",
+                      "from": { "path": ["packages/**"] },
+                      "to": { "path": ["packages/server/**"] } },
+                    { "name": "src-core", "kind": "allow",
+                      "from": { "path": ["src/**"] },
+                      "to": { "path": ["src/core/index.ts"] } },
+                    { "name": "app-shared", "kind": "allow",
+                      "from": { "path": ["src/app.ts"] },
+                      "to": { "path": ["packages/shared/**"] } }
+                ] }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let violations = graph(root).check_rules(&rules, root);
+
+        assert_eq!(violations.len(), 2);
+        assert!(
+            violations
+                .iter()
+                .all(|violation| violation.rule == "src-core")
+        );
+        assert_eq!(violations[0].from_path, root.join("src/app.ts"));
+        assert_eq!(violations[1].from_path, root.join("src/core/index.ts"));
+    }
+}
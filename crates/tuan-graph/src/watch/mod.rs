@@ -0,0 +1,116 @@
+use crate::{graph::GraphDiff, graph_builders::Typescript};
+use notify_debouncer_full::{
+    DebounceEventResult, Debouncer, RecommendedCache, new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode},
+};
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+pub use notify_debouncer_full::notify::Error;
+
+type SharedDebouncer = Arc<Mutex<Option<Debouncer<RecommendedWatcher, RecommendedCache>>>>;
+
+/// Watches the project root of a [`Typescript`] builder, applies incremental updates on
+/// debounced file system events and reports every non-empty [`GraphDiff`]. The builder is
+/// shared, so that it can still be queried while watched; it is only locked during updates.
+///
+/// Only the directories discovery enters are watched, one by one, so that `node_modules` and
+/// other ignored directories do not use up watches. Directories are added as they are created.
+pub struct Watcher {
+    debouncer: SharedDebouncer,
+}
+
+impl Watcher {
+    pub fn new<F>(
+        builder: Arc<Mutex<Typescript>>,
+        debounce: Duration,
+        mut on_change: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(&GraphDiff) + Send + 'static,
+    {
+        let project_root = builder.lock().unwrap().project_root().to_path_buf();
+        let shared_debouncer: SharedDebouncer = Arc::new(Mutex::new(None));
+        // Weak, so that the debouncer does not keep itself alive through its own handler.
+        let handler_debouncer = Arc::downgrade(&shared_debouncer);
+        let handler_builder = builder.clone();
+
+        let mut debouncer =
+            new_debouncer(
+                debounce,
+                None,
+                move |result: DebounceEventResult| match result {
+                    Ok(events) => {
+                        let mut builder = handler_builder.lock().unwrap();
+                        let created_dirs = events
+                            .iter()
+                            .filter(|event| event.kind.is_create())
+                            .flat_map(|event| &event.paths)
+                            .filter(|path| path.is_dir() && !builder.is_ignored(path))
+                            .collect::<Vec<_>>();
+                        if !created_dirs.is_empty()
+                            && let Some(debouncer) = handler_debouncer.upgrade()
+                            && let Some(debouncer) = debouncer.lock().unwrap().as_mut()
+                        {
+                            for dir in created_dirs {
+                                if let Err(e) = watch_directories(debouncer, &builder, dir) {
+                                    tracing::error!("Error watching {}: {}", dir.display(), e);
+                                }
+                            }
+                        }
+
+                        let paths = events
+                            .into_iter()
+                            .flat_map(|event| event.event.paths)
+                            .filter(|path| builder.is_relevant_change(path))
+                            .collect::<HashSet<_>>();
+                        if paths.is_empty() {
+                            return;
+                        }
+
+                        let diff = builder.update(paths);
+                        drop(builder);
+                        if !diff.is_empty() {
+                            on_change(&diff);
+                        }
+                    }
+                    Err(errors) => {
+                        for error in errors {
+                            tracing::error!("Error watching {}: {}", project_root.display(), error);
+                        }
+                    }
+                },
+            )?;
+        {
+            let builder = builder.lock().unwrap();
+            watch_directories(&mut debouncer, &builder, builder.project_root())?;
+        }
+        *shared_debouncer.lock().unwrap() = Some(debouncer);
+
+        Ok(Self {
+            debouncer: shared_debouncer,
+        })
+    }
+
+    pub fn stop(self) {
+        let debouncer = self.debouncer.lock().unwrap().take();
+        if let Some(debouncer) = debouncer {
+            debouncer.stop();
+        }
+    }
+}
+
+fn watch_directories(
+    debouncer: &mut Debouncer<RecommendedWatcher, RecommendedCache>,
+    builder: &Typescript,
+    dir: &Path,
+) -> Result<(), Error> {
+    for dir in builder.directories(dir) {
+        debouncer.watch(&dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(())
+}
@@ -4,9 +4,32 @@ mod tokens_scorer;
 
 use crate::tokens_scorer::TokensScorer;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tuan_walker::{DEFAULT_EXCLUDE, SOURCE_EXTENSIONS, SourceFilter};
+
+/// Which files [`ProjectLabeler::from_project_with_options`] labels, with the same meaning as
+/// the options of the same name of the `tuan-graph` TypeScript builder.
+#[derive(Debug, Clone)]
+pub struct ProjectOptions {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub extensions: Vec<String>,
+    pub include_declarations: bool,
+}
+
+impl Default for ProjectOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: DEFAULT_EXCLUDE.iter().map(|glob| glob.to_string()).collect(),
+            extensions: Vec::new(),
+            include_declarations: true,
+        }
+    }
+}
 
 pub struct ProjectLabeler {
+    files: Vec<PathBuf>,
     file_tokens_scorer: TokensScorer,
     path_tokens_scorer: TokensScorer,
 }
@@ -17,7 +40,37 @@ impl ProjectLabeler {
             .into_iter()
             .map(PathBuf::from)
             .collect::<Vec<_>>();
+        Self::with_files(project_path, files)
+    }
 
+    /// Labels the source files of `project_path` that are not ignored, the same files
+    /// `tuan-graph` discovers with its default options.
+    pub fn from_project(project_path: String) -> Self {
+        Self::from_project_with_options(project_path, ProjectOptions::default())
+    }
+
+    /// Labels the source files of `project_path` that are not ignored, the same files
+    /// `tuan-graph` discovers with the same options.
+    pub fn from_project_with_options(project_path: String, options: ProjectOptions) -> Self {
+        let extensions = SOURCE_EXTENSIONS
+            .iter()
+            .chain(&tuan_sfc::COMPONENT_EXTENSIONS)
+            .map(|extension| extension.to_string())
+            .chain(
+                options
+                    .extensions
+                    .iter()
+                    .map(|extension| extension.trim_start_matches('.').to_string()),
+            );
+        let files = SourceFilter::new(Path::new(&project_path), extensions)
+            .include(&options.include)
+            .exclude(&options.exclude)
+            .include_declarations(options.include_declarations)
+            .source_files();
+        Self::with_files(project_path, files)
+    }
+
+    fn with_files(project_path: String, files: Vec<PathBuf>) -> Self {
         let mut file_tokens_scorer = TokensScorer::new(move |path| tokenizers::file(path));
         file_tokens_scorer.ingest(&files);
 
@@ -25,7 +78,12 @@ impl ProjectLabeler {
             TokensScorer::new(move |path| Ok(tokenizers::path(path, &project_path)));
         path_tokens_scorer.ingest(&files);
 
-        Self { file_tokens_scorer, path_tokens_scorer }
+        Self { files, file_tokens_scorer, path_tokens_scorer }
+    }
+
+    /// The project files labels are scored against.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
     }
 
     pub fn label_files(
@@ -6,14 +6,21 @@ use std::path::Path;
 pub fn tokenize_file(file_path: &Path) -> Result<Vec<String>, std::io::Error> {
     let source_code = std::fs::read_to_string(&file_path)?;
     let allocator = oxc_allocator::Allocator::default();
-    let source_type = match file_path.extension().and_then(|s| s.to_str()) {
-        Some("tsx") => SourceType::tsx(),
-        Some("ts") => SourceType::ts(),
-        Some("jsx") => SourceType::jsx(),
-        Some("js") => SourceType::unambiguous().with_jsx(true).with_module(true),
-        Some("mjs") => SourceType::mjs(),
-        Some("cjs") => SourceType::cjs(),
-        _ => SourceType::unambiguous(),
+    // Only the scripts of single-file components are tokenized.
+    let (source_code, source_type) = match tuan_sfc::script_source(file_path, &source_code) {
+        Some(script) => script,
+        None => {
+            let source_type = match file_path.extension().and_then(|s| s.to_str()) {
+                Some("tsx") => SourceType::tsx(),
+                Some("ts") => SourceType::ts(),
+                Some("jsx") => SourceType::jsx(),
+                Some("js") => SourceType::unambiguous().with_jsx(true).with_module(true),
+                Some("mjs") => SourceType::mjs(),
+                Some("cjs") => SourceType::cjs(),
+                _ => SourceType::unambiguous(),
+            };
+            (source_code, source_type)
+        }
     };
 
     let mut tokens = vec![];
@@ -0,0 +1,347 @@
+use oxc_span::SourceType;
+use std::path::Path;
+
+/// Extensions of single-file components, whose code lives in `<script>` blocks, and for Astro
+/// in the frontmatter.
+pub const COMPONENT_EXTENSIONS: [&str; 3] = ["vue", "svelte", "astro"];
+
+/// A `<script>` or `<style>` block, or the frontmatter of an Astro component.
+#[derive(Debug)]
+pub struct Block<'a> {
+    pub content: &'a str,
+    /// Byte offset of `content` in the component.
+    pub offset: usize,
+    /// Boolean attributes, like `setup`, have an empty value.
+    pub attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Block<'a> {
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Whether `path` is a single-file component, see [`COMPONENT_EXTENSIONS`].
+pub fn is_component(path: &Path) -> bool {
+    component_extension(path).is_some()
+}
+
+fn component_extension(path: &Path) -> Option<&str> {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .filter(|extension| COMPONENT_EXTENSIONS.contains(extension))
+}
+
+/// The scripts of a component as a single program, along with how to parse it. Everything
+/// outside of the scripts is blanked out, so that offsets and lines still point into the
+/// component. Returns `None` for files that are not components.
+pub fn script_source(path: &Path, source: &str) -> Option<(String, SourceType)> {
+    let extension = component_extension(path)?;
+    let blocks = match extension {
+        "astro" => astro_scripts(source),
+        _ => script_blocks(source),
+    };
+    let mut script = source
+        .bytes()
+        .map(|byte| if byte == b'\n' { '\n' } else { ' ' })
+        .collect::<String>();
+    for block in &blocks {
+        script.replace_range(
+            block.offset..block.offset + block.content.len(),
+            block.content,
+        );
+    }
+
+    // Astro scripts are always TypeScript.
+    let lang = match extension {
+        "astro" => Some("ts"),
+        _ => blocks.iter().find_map(|block| block.attribute("lang")),
+    };
+    let source_type = match lang {
+        Some("ts" | "typescript") => SourceType::ts(),
+        Some("tsx") => SourceType::tsx(),
+        Some("jsx") => SourceType::jsx(),
+        _ => SourceType::mjs(),
+    };
+    Some((script, source_type))
+}
+
+/// Every `<script>` block of `source`, skipping the ones in HTML comments.
+pub fn script_blocks(source: &str) -> Vec<Block<'_>> {
+    blocks_from(source, 0, "script")
+}
+
+/// Every `<style>` block of a component, skipping the ones in HTML comments and, for Astro, in
+/// the frontmatter. Returns nothing for files that are not components.
+pub fn style_blocks<'a>(path: &Path, source: &'a str) -> Vec<Block<'a>> {
+    match component_extension(path) {
+        Some("astro") => blocks_from(source, markup_start(source), "style"),
+        Some(_) => blocks_from(source, 0, "style"),
+        None => Vec::new(),
+    }
+}
+
+/// The frontmatter of an Astro component, between the leading `---` fences.
+pub fn frontmatter(source: &str) -> Option<Block<'_>> {
+    let start = source.len() - source.trim_start().len();
+    let content = source[start..].strip_prefix("---")?;
+    let content_start = start + 3;
+    let content_end = content_start + content.find("\n---")? + 1;
+    Some(Block {
+        content: &source[content_start..content_end],
+        offset: content_start,
+        attributes: Vec::new(),
+    })
+}
+
+/// The frontmatter and the client scripts of an Astro component. `is:inline` scripts are left
+/// out, as Astro does not bundle them.
+fn astro_scripts(source: &str) -> Vec<Block<'_>> {
+    frontmatter(source)
+        .into_iter()
+        .chain(
+            blocks_from(source, markup_start(source), "script")
+                .into_iter()
+                .filter(|block| block.attribute("is:inline").is_none()),
+        )
+        .collect()
+}
+
+/// Where the markup of an Astro component starts, after its frontmatter.
+fn markup_start(source: &str) -> usize {
+    frontmatter(source).map_or(0, |frontmatter| {
+        frontmatter.offset + frontmatter.content.len() + 3
+    })
+}
+
+/// Every `<{name}>` block of `source` from `start`. `name` is lowercase.
+fn blocks_from<'a>(source: &'a str, start: usize, name: &str) -> Vec<Block<'a>> {
+    let opening = format!("<{name}");
+    let closing = format!("</{name}");
+    // Same byte offsets as `source`, to find tags case-insensitively.
+    let lowercase = source.to_ascii_lowercase();
+    let mut blocks = Vec::new();
+
+    let mut idx = start;
+    while let Some(found) = lowercase[idx..].find('<') {
+        let tag_start = idx + found;
+        let tag = &lowercase[tag_start..];
+        if tag.starts_with("<!--") {
+            idx = tag
+                .find("-->")
+                .map_or(source.len(), |end| tag_start + end + 3);
+            continue;
+        }
+        let is_block = tag.starts_with(&opening)
+            && tag[opening.len()..]
+                .starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/');
+        if !is_block {
+            idx = tag_start + 1;
+            continue;
+        }
+
+        let Some(OpeningTag {
+            attributes,
+            end: content_start,
+            self_closing,
+        }) = parse_opening_tag(source, tag_start + opening.len())
+        else {
+            break;
+        };
+        let content_end = if self_closing {
+            content_start
+        } else {
+            lowercase[content_start..]
+                .find(&closing)
+                .map_or(source.len(), |end| content_start + end)
+        };
+        blocks.push(Block {
+            content: &source[content_start..content_end],
+            offset: content_start,
+            attributes,
+        });
+        idx = content_end;
+    }
+
+    blocks
+}
+
+struct OpeningTag<'a> {
+    attributes: Vec<(&'a str, &'a str)>,
+    /// Byte offset right after the `>`.
+    end: usize,
+    self_closing: bool,
+}
+
+/// Parses the rest of the opening tag whose name ends at `idx`. Returns `None` when the tag is
+/// never closed.
+fn parse_opening_tag(source: &str, mut idx: usize) -> Option<OpeningTag<'_>> {
+    let bytes = source.as_bytes();
+    let skip_whitespace = |idx: &mut usize| {
+        while bytes.get(*idx).is_some_and(u8::is_ascii_whitespace) {
+            *idx += 1;
+        }
+    };
+
+    let mut attributes = Vec::new();
+    loop {
+        skip_whitespace(&mut idx);
+        match bytes.get(idx)? {
+            b'>' => {
+                return Some(OpeningTag {
+                    attributes,
+                    end: idx + 1,
+                    self_closing: false,
+                });
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'>') => {
+                return Some(OpeningTag {
+                    attributes,
+                    end: idx + 2,
+                    self_closing: true,
+                });
+            }
+            _ => {}
+        }
+
+        let name_start = idx;
+        while !matches!(bytes.get(idx)?, b'=' | b'>' | b'/') && !bytes[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+        let name = &source[name_start..idx];
+        if name.is_empty() {
+            idx += 1;
+            continue;
+        }
+
+        skip_whitespace(&mut idx);
+        if bytes.get(idx) != Some(&b'=') {
+            attributes.push((name, ""));
+            continue;
+        }
+        idx += 1;
+        skip_whitespace(&mut idx);
+        let value = match bytes.get(idx)? {
+            quote @ (b'"' | b'\'') => {
+                let value_start = idx + 1;
+                idx = value_start + source[value_start..].find(*quote as char)?;
+                let value = &source[value_start..idx];
+                idx += 1;
+                value
+            }
+            _ => {
+                let value_start = idx;
+                while !matches!(bytes.get(idx)?, b'>') && !bytes[idx].is_ascii_whitespace() {
+                    idx += 1;
+                }
+                &source[value_start..idx]
+            }
+        };
+        attributes.push((name, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_extracts_vue_script_blocks() {
+        let source = r#"<template>
+  <Button @click="count++">{{ count }}</Button>
+</template>
+
+<!-- <script>import Old from './Old.vue'</script> -->
+<script lang="ts">
+export default { name: 'Counter' }
+</script>
+
+<SCRIPT setup lang='ts' generic="T extends Record<string, unknown>">
+import Button from './Button.vue'
+</SCRIPT>
+
+<style scoped>
+.button { color: red; }
+</style>
+"#;
+
+        let blocks = script_blocks(source);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].attribute("lang"), Some("ts"));
+        assert_eq!(
+            blocks[0].content.trim(),
+            "export default { name: 'Counter' }"
+        );
+        assert_eq!(blocks[1].attribute("setup"), Some(""));
+        assert_eq!(
+            blocks[1].attribute("generic"),
+            Some("T extends Record<string, unknown>")
+        );
+        assert_eq!(
+            blocks[1].content.trim(),
+            "import Button from './Button.vue'"
+        );
+
+        let (script, source_type) = script_source(Path::new("Counter.vue"), source).unwrap();
+        assert!(source_type.is_typescript());
+        assert_eq!(script.len(), source.len());
+        assert_eq!(script.lines().count(), source.lines().count());
+        let offset = script.find("import Button").unwrap();
+        assert_eq!(&source[offset..offset + 13], "import Button");
+        assert!(!script.contains("template") && !script.contains("Old"));
+
+        assert!(script_source(Path::new("index.ts"), source).is_none());
+
+        let styles = style_blocks(Path::new("Counter.vue"), source);
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles[0].attribute("scoped"), Some(""));
+        assert_eq!(styles[0].content.trim(), ".button { color: red; }");
+        assert_eq!(
+            &source[styles[0].offset..styles[0].offset + styles[0].content.len()],
+            styles[0].content
+        );
+    }
+
+    #[test]
+    fn it_extracts_svelte_and_astro_scripts() {
+        let svelte = r#"<script context="module" lang="ts">
+import { load } from './load';
+</script>
+
+<script lang="ts">
+import Widget from './Widget.svelte';
+</script>
+
+{#if count < 10}<Widget />{/if}
+"#;
+        let (script, source_type) = script_source(Path::new("Widgets.svelte"), svelte).unwrap();
+        assert!(source_type.is_typescript());
+        assert!(script.contains("import { load } from './load';"));
+        assert!(script.contains("import Widget from './Widget.svelte';"));
+        assert!(!script.contains("{#if"));
+
+        let astro = r#"---
+import Layout from '../layouts/Layout.astro';
+const items: Array<string> = [];
+---
+<Layout>
+  <script>
+    import { track } from '../analytics';
+  </script>
+  <script is:inline>
+    import('./inline.js');
+  </script>
+</Layout>
+"#;
+        let (script, source_type) = script_source(Path::new("index.astro"), astro).unwrap();
+        assert!(source_type.is_typescript());
+        assert!(script.contains("import Layout from '../layouts/Layout.astro';"));
+        assert!(script.contains("const items: Array<string> = [];"));
+        assert!(script.contains("import { track } from '../analytics';"));
+        assert!(!script.contains("inline") && !script.contains("---"));
+        assert_eq!(script.len(), astro.len());
+    }
+}
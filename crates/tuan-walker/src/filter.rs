@@ -0,0 +1,214 @@
+use crate::{DirEntry, walk_builder};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::{Path, PathBuf};
+
+/// Extensions of the JavaScript and TypeScript files parsed by default.
+pub const SOURCE_EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Extensions of every JavaScript and TypeScript module, including `mts` and `cts` files,
+/// which are only parsed when asked for.
+pub const MODULE_EXTENSIONS: [&str; 8] = ["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Directories skipped by default. `node_modules` and `.git` are always skipped.
+pub const DEFAULT_EXCLUDE: [&str; 8] = [
+    "**/dist",
+    "**/build",
+    "**/out",
+    "**/coverage",
+    "**/.next",
+    "**/.svelte-kit",
+    "**/.astro",
+    "**/storybook-static",
+];
+
+/// Skipped whatever the options say.
+const ALWAYS_EXCLUDED_DIRS: [&str; 2] = ["node_modules", ".git"];
+
+/// Decides which files of a project are source files, so that every tool sees the same files.
+/// Include and exclude globs are matched against project-relative paths.
+#[derive(Debug, Clone)]
+pub struct SourceFilter {
+    root: PathBuf,
+    extensions: Vec<String>,
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    include_declarations: bool,
+}
+
+impl SourceFilter {
+    /// Files of `root` with one of `extensions`, outside of [`DEFAULT_EXCLUDE`]. Declaration
+    /// files are included.
+    pub fn new<I, S>(root: &Path, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            root: root.to_path_buf(),
+            extensions: extensions.into_iter().map(Into::into).collect(),
+            include: None,
+            exclude: glob_set(&DEFAULT_EXCLUDE),
+            include_declarations: true,
+        }
+    }
+
+    /// Only keeps the files matching one of `globs`. Empty means every file.
+    pub fn include<S: AsRef<str>>(mut self, globs: &[S]) -> Self {
+        self.include = (!globs.is_empty()).then(|| glob_set(globs));
+        self
+    }
+
+    /// Skips the files and directories matching one of `globs`, instead of
+    /// [`DEFAULT_EXCLUDE`].
+    pub fn exclude<S: AsRef<str>>(mut self, globs: &[S]) -> Self {
+        self.exclude = glob_set(globs);
+        self
+    }
+
+    /// Whether `.d.ts`, `.d.mts` and `.d.cts` files are source files.
+    pub fn include_declarations(mut self, include_declarations: bool) -> Self {
+        self.include_declarations = include_declarations;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn has_source_extension(&self, path: &Path) -> bool {
+        let Some(extension) = path.extension().and_then(|s| s.to_str()) else {
+            return false;
+        };
+        if !self.extensions.iter().any(|source| source == extension) {
+            return false;
+        }
+        self.include_declarations
+            || !path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .is_some_and(|name| {
+                    [".d.ts", ".d.mts", ".d.cts"]
+                        .iter()
+                        .any(|d| name.ends_with(d))
+                })
+    }
+
+    /// Whether `path` or one of its parent directories is excluded.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let relative_path = path.strip_prefix(&self.root).unwrap_or(path);
+        relative_path.components().any(|component| {
+            ALWAYS_EXCLUDED_DIRS
+                .iter()
+                .any(|dir| component.as_os_str() == *dir)
+        }) || relative_path
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .any(|ancestor| self.exclude.is_match(ancestor))
+    }
+
+    /// Whether `path` is a source file, not taking ignore files into account.
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        self.has_source_extension(path)
+            && !self.is_excluded(path)
+            && self.include.as_ref().is_none_or(|include| {
+                include.is_match(path.strip_prefix(&self.root).unwrap_or(path))
+            })
+    }
+
+    /// Walks `dir` without entering excluded directories, see [`walk_builder`].
+    pub fn walk(&self, dir: &Path, follow_links: bool) -> impl Iterator<Item = DirEntry> {
+        let filter = self.clone();
+        walk_builder(dir)
+            .follow_links(follow_links)
+            .filter_entry(move |entry| !filter.is_excluded(entry.path()))
+            .build()
+            .filter_map(Result::ok)
+    }
+
+    /// Every source file of the project that is not ignored.
+    pub fn source_files(&self) -> Vec<PathBuf> {
+        self.walk(&self.root, false)
+            .filter(|entry| {
+                entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_file())
+                    && self.is_source_file(entry.path())
+            })
+            .map(DirEntry::into_path)
+            .collect()
+    }
+}
+
+/// Invalid globs are skipped with a warning, so that a typo does not prevent finding files.
+fn glob_set<S: AsRef<str>>(globs: &[S]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        match Glob::new(glob.as_ref()) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => tracing::warn!("Ignoring invalid glob {}: {}", glob.as_ref(), e),
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid globs: {}", e);
+        GlobSet::empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_filters_source_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let write = |path: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        };
+        write("src/index.ts");
+        write("src/types.d.ts");
+        write("src/App.vue");
+        write("src/styles.css");
+        write("scripts/build.js");
+        write("dist/index.js");
+        write("node_modules/react/index.js");
+
+        let source_files = |filter: SourceFilter| {
+            let mut files = filter
+                .source_files()
+                .into_iter()
+                .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+                .collect::<Vec<_>>();
+            files.sort_unstable();
+            files
+        };
+        assert_eq!(
+            source_files(SourceFilter::new(root, SOURCE_EXTENSIONS)),
+            vec![
+                PathBuf::from("scripts/build.js"),
+                PathBuf::from("src/index.ts"),
+                PathBuf::from("src/types.d.ts"),
+            ]
+        );
+        assert_eq!(
+            source_files(
+                SourceFilter::new(root, SOURCE_EXTENSIONS.iter().chain(&["vue"]).copied())
+                    .exclude(&["scripts"])
+                    .include_declarations(false)
+            ),
+            vec![
+                PathBuf::from("dist/index.js"),
+                PathBuf::from("src/App.vue"),
+                PathBuf::from("src/index.ts"),
+            ]
+        );
+        assert_eq!(
+            source_files(SourceFilter::new(root, SOURCE_EXTENSIONS).include(&["scripts/**"])),
+            vec![PathBuf::from("scripts/build.js")]
+        );
+    }
+}
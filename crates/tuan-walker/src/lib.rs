@@ -0,0 +1,169 @@
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    overrides::OverrideBuilder,
+};
+use std::path::{Path, PathBuf};
+
+mod filter;
+
+pub use filter::{DEFAULT_EXCLUDE, MODULE_EXTENSIONS, SOURCE_EXTENSIONS, SourceFilter};
+pub use ignore::{DirEntry, WalkBuilder};
+
+/// Project-specific ignore file, with the same syntax as `.gitignore`.
+pub const IGNORE_FILE_NAME: &str = ".tuanignore";
+
+/// Walks `root` the way git lists project files: `.gitignore`, `.ignore`, `.git/info/exclude`
+/// and `.tuanignore` files are respected, the deepest one winning, even outside of a git
+/// repository. Hidden files are kept, but `.git` and `node_modules` are never entered.
+pub fn walk_builder(root: &Path) -> WalkBuilder {
+    let mut overrides = OverrideBuilder::new(root);
+    overrides.add("!.git/").expect("valid glob");
+    overrides.add("!node_modules/").expect("valid glob");
+
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .git_global(false)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE_NAME)
+        .overrides(overrides.build().expect("valid overrides"));
+    builder
+}
+
+/// Every file of the project that is not ignored.
+pub fn project_files(root: &Path) -> Vec<PathBuf> {
+    walk_builder(root)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+        })
+        .map(DirEntry::into_path)
+        .collect()
+}
+
+/// Whether [`project_files`] skips `path`, either because it is ignored or because one of its
+/// parent directories is. Paths outside of `root` are never ignored.
+pub fn is_ignored(root: &Path, path: &Path) -> bool {
+    let Ok(relative_path) = path.strip_prefix(root) else {
+        return false;
+    };
+
+    // Ignore files of every directory containing `path`, outermost first.
+    let mut matchers = path
+        .ancestors()
+        .skip(1)
+        .map(|dir| (dir, ignore_matchers(dir)))
+        .collect::<Vec<_>>();
+    matchers.reverse();
+
+    let components = relative_path.components().collect::<Vec<_>>();
+    let mut current = root.to_path_buf();
+    for (idx, component) in components.iter().enumerate() {
+        current.push(component);
+        if [".git", "node_modules"].contains(&component.as_os_str().to_str().unwrap_or_default()) {
+            return true;
+        }
+
+        // Like the walker, the deepest match of each kind of ignore file wins, and the kinds
+        // are then tried from the highest precedence.
+        let is_dir = idx + 1 < components.len() || current.is_dir();
+        let matched = (0..IGNORE_FILE_KINDS).find_map(|kind| {
+            matchers
+                .iter()
+                .rev()
+                .filter(|(dir, _)| current.starts_with(dir) && current != *dir)
+                .map(|(_, matchers)| matchers[kind].matched(&current, is_dir))
+                .find(|matched| !matched.is_none())
+        });
+        if matched.is_some_and(|matched| matched.is_ignore()) {
+            return true;
+        }
+    }
+    false
+}
+
+/// `.tuanignore`, `.ignore`, `.gitignore` and `.git/info/exclude`.
+const IGNORE_FILE_KINDS: usize = 4;
+
+/// Matchers for the ignore files of `dir`, from the highest precedence to the lowest.
+fn ignore_matchers(dir: &Path) -> [Gitignore; IGNORE_FILE_KINDS] {
+    [
+        Path::new(IGNORE_FILE_NAME),
+        Path::new(".ignore"),
+        Path::new(".gitignore"),
+        Path::new(".git/info/exclude"),
+    ]
+    .map(|file| {
+        let file = dir.join(file);
+        if !file.is_file() {
+            return Gitignore::empty();
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        // Invalid lines are skipped, as when walking.
+        builder.add(file);
+        builder.build().unwrap_or_else(|_| Gitignore::empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_respects_nested_ignore_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(".gitignore", "generated/\n*.log\n");
+        write(".ignore", "tmp/\n");
+        write(IGNORE_FILE_NAME, "scratch.ts\n");
+        write("src/.gitignore", "!keep.log\n");
+        write("src/index.ts", "");
+        write("src/keep.log", "");
+        write("src/debug.log", "");
+        write("src/scratch.ts", "");
+        write("generated/client.ts", "");
+        write("tmp/a.ts", "");
+        write(".storybook/main.ts", "");
+        write(".git/info/exclude", "*.tmp\n");
+        write("src/local.tmp", "");
+        write("node_modules/react/index.js", "");
+
+        let mut files = project_files(root)
+            .into_iter()
+            .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+            // Skips the ignore files themselves.
+            .filter(|path| path.extension().is_some())
+            .collect::<Vec<_>>();
+        files.sort_unstable();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from(".storybook/main.ts"),
+                PathBuf::from("src/index.ts"),
+                PathBuf::from("src/keep.log"),
+            ]
+        );
+
+        for path in [
+            "generated/client.ts",
+            "tmp/a.ts",
+            "src/scratch.ts",
+            "src/debug.log",
+            "src/local.tmp",
+            "node_modules/react/index.js",
+        ] {
+            assert!(is_ignored(root, &root.join(path)), "{path}");
+        }
+        for path in ["src/keep.log", "src/index.ts", ".storybook/main.ts"] {
+            assert!(!is_ignored(root, &root.join(path)), "{path}");
+        }
+    }
+}
@@ -1,9 +1,10 @@
 import path from 'node:path';
+import { mkdtempSync, realpathSync, rmSync, writeFileSync } from 'node:fs';
 import { execSync } from 'node:child_process';
 import { tmpdir } from 'node:os';
 import anyTest, { type TestFn } from 'ava'
 
-import { typescript } from '../index'
+import { DiagnosticKind, EdgeKind, ExportFormat, NodeKind, typescript, type GraphDiff } from '../index'
 
 const test = anyTest as TestFn<{ projectDir: string }>;
 
@@ -52,3 +53,196 @@ test('clusterize', (t) => {
     t.truthy(cluster.members.length > 0)
   }
 })
+
+test('export', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const clusters = graph.clusterize(100)
+
+  t.truthy(graph.export(ExportFormat.Dot, clusters).startsWith('digraph {'))
+  t.truthy(graph.export(ExportFormat.GraphMl).includes('<graphml'))
+  t.truthy(graph.export(ExportFormat.Gexf).includes('<gexf'))
+  t.is(Object.keys(JSON.parse(graph.export(ExportFormat.JsonGraph)).graph.nodes).length, graph.nodes.length)
+})
+
+test('queries', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const edge = graph.edges[0]
+
+  t.true(graph.dependencies(edge.from, 1).includes(edge.to))
+  t.true(graph.dependents(edge.to, 1).includes(edge.from))
+  t.deepEqual(graph.shortestPath(edge.from, edge.to), [edge.from, edge.to])
+  t.deepEqual(graph.simplePaths(edge.from, edge.to, 1), [[edge.from, edge.to]])
+  t.true(graph.isReachable(edge.from, edge.to))
+})
+
+test('cycles', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const all = graph.cycles()
+  const runtime = graph.cycles({ ignoreTypeOnly: true, ignoreDynamic: true })
+
+  const cyclic = new Set(all.flatMap((cycle) => cycle.members))
+  t.true(runtime.every((cycle) => cycle.members.every((id) => cyclic.has(id))))
+  for (const cycle of all) {
+    t.true(cycle.path.length > 0)
+    t.true(cycle.path.every((id) => cycle.members.includes(id)))
+  }
+})
+
+test('dead files', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const dead = graph.deadFiles(['**/*.{spec,test}.ts'])
+
+  t.true(dead.length < graph.nodes.length)
+  t.deepEqual(
+    graph.nodes.filter((node) => node.dead).map((node) => node.id).sort(),
+    [...dead].sort(),
+  )
+})
+
+test('impact', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const edge = graph.edges[0]
+  const changed = graph.nodes.find((node) => node.id === edge.to)!
+  const impact = graph.impact([changed.filePath])
+
+  t.deepEqual(impact.changed, [changed.id])
+  t.true(impact.affected.includes(edge.from))
+  t.true(impact.tests.every((id) => impact.affected.includes(id)))
+})
+
+test('metrics', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const metrics = graph.metrics()
+
+  t.is(metrics.length, graph.nodes.length)
+  t.true(Math.abs(metrics.reduce((sum, m) => sum + m.pageRank, 0) - 1) < 1e-6)
+  t.true(metrics.some((m) => m.fanIn > 0 && m.depth > 0))
+})
+
+test('check rules', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const rulesPath = path.join(tmpdir(), 'tuan-forbid-all.rules.json')
+  writeFileSync(
+    rulesPath,
+    JSON.stringify({
+      rules: [{ name: 'no-imports', kind: 'deny', from: { path: ['**'] }, to: { path: ['**'] } }],
+    }),
+  )
+
+  const violations = graph.checkRules(rulesPath)
+
+  t.is(violations.length, graph.edges.length)
+  t.true(violations.every((violation) => violation.rule === 'no-imports'))
+})
+
+test('package graph', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const packageGraph = graph.packageGraph()
+
+  t.true(packageGraph.packages.length > 1)
+  for (const edge of packageGraph.edges) {
+    t.not(edge.from, edge.to)
+    t.is(edge.count, edge.edges.length)
+  }
+})
+
+test('compound graph', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const compound = graph.compound()
+
+  const expanded = compound.view()
+  t.is(expanded.nodes.filter((node) => node.fileId !== undefined).length, graph.nodes.length)
+
+  compound.collapseToDepth(1)
+  const collapsed = compound.view()
+  t.true(collapsed.nodes.length < expanded.nodes.length)
+  t.true(collapsed.edges.length <= expanded.edges.length)
+
+  compound.expandAll()
+  t.is(compound.view().nodes.length, expanded.nodes.length)
+})
+
+test('typescript options', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const included = typescript.getGraph(t.context.projectDir, null, { include: ['packages/**'] })
+  const withoutDeclarations = typescript.getGraph(t.context.projectDir, null, { includeDeclarations: false })
+
+  t.true(included.nodes.length > 0)
+  t.true(included.nodes.every((node) => node.filePath.includes(`${path.sep}packages${path.sep}`)))
+  t.false(withoutDeclarations.nodes.some((node) => node.filePath.endsWith('.d.ts')))
+  t.true(withoutDeclarations.nodes.length <= graph.nodes.length)
+})
+
+test('external packages', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir, null, { externalPackages: true })
+  const externals = graph.nodes.filter((node) => node.kind === NodeKind.ExternalPackage)
+
+  t.true(externals.length > 0)
+  t.true(graph.edges.some((edge) => externals.some((node) => node.id === edge.to)))
+  t.is(graph.nodes.length - externals.length, typescript.getGraph(t.context.projectDir).nodes.length)
+})
+
+test('assets', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir, null, { assets: true })
+  const kinds = new Set(graph.nodes.map((node) => node.kind))
+
+  t.true(kinds.has(NodeKind.Source))
+  for (const edge of graph.edges) {
+    if (edge.kind === EdgeKind.Asset) {
+      t.not(graph.nodes.find((node) => node.id === edge.to)?.kind, NodeKind.Source)
+    }
+  }
+})
+
+test('diagnostics', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  const diagnostics = graph.diagnostics()
+
+  for (const diagnostic of diagnostics) {
+    t.truthy(diagnostic.message)
+    if (diagnostic.kind === DiagnosticKind.UnresolvedImport) {
+      t.truthy(diagnostic.specifier)
+      t.truthy(diagnostic.location)
+    }
+  }
+})
+
+test('compare', (t) => {
+  const graph = typescript.getGraph(t.context.projectDir)
+  t.deepEqual(graph.compare(typescript.getGraph(t.context.projectDir)), {
+    addedFiles: [],
+    removedFiles: [],
+    addedEdges: [],
+    removedEdges: [],
+    newCycles: [],
+  })
+
+  const comparison = typescript.compareRevisions(t.context.projectDir, 'HEAD~1', 'HEAD')
+  for (const edge of comparison.addedEdges) {
+    t.false(path.isAbsolute(edge.from))
+  }
+})
+
+test('watch', async (t) => {
+  const projectDir = realpathSync(mkdtempSync(path.join(tmpdir(), 'tuan-watch-')))
+  try {
+    writeFileSync(path.join(projectDir, 'core.ts'), `export const core = 1\n`)
+    const graph = typescript.getGraph(projectDir)
+    const nodeCount = graph.nodes.length
+
+    const diff = await new Promise<GraphDiff>((resolve) => {
+      const watcher = graph.watch((diff) => {
+        watcher.close()
+        resolve(diff)
+      }, 50)
+      writeFileSync(path.join(projectDir, 'watched.ts'), `import './core'\n`)
+    })
+
+    t.is(diff.addedNodes.length, 1)
+    t.is(diff.addedEdges.length, 1)
+    t.is(graph.nodes.length, nodeCount + 1)
+    t.deepEqual(graph.diagnostics(), [])
+  } finally {
+    rmSync(projectDir, { recursive: true, force: true })
+  }
+})
@@ -57,4 +57,12 @@ const {
 })
 export default __napiModule.exports
 export const Graph = __napiModule.exports.Graph
+export const CompoundGraph = __napiModule.exports.CompoundGraph
+export const GraphWatcher = __napiModule.exports.GraphWatcher
+export const DiagnosticKind = __napiModule.exports.DiagnosticKind
+export const EdgeKind = __napiModule.exports.EdgeKind
+export const ExportFormat = __napiModule.exports.ExportFormat
+export const NodeKind = __napiModule.exports.NodeKind
+export const RuleKind = __napiModule.exports.RuleKind
+export const Severity = __napiModule.exports.Severity
 export const typescript = __napiModule.exports.typescript
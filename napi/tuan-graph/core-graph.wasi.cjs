@@ -109,4 +109,12 @@ const { instance: __napiInstance, module: __wasiModule, napiModule: __napiModule
 })
 module.exports = __napiModule.exports
 module.exports.Graph = __napiModule.exports.Graph
+module.exports.CompoundGraph = __napiModule.exports.CompoundGraph
+module.exports.GraphWatcher = __napiModule.exports.GraphWatcher
+module.exports.DiagnosticKind = __napiModule.exports.DiagnosticKind
+module.exports.EdgeKind = __napiModule.exports.EdgeKind
+module.exports.ExportFormat = __napiModule.exports.ExportFormat
+module.exports.NodeKind = __napiModule.exports.NodeKind
+module.exports.RuleKind = __napiModule.exports.RuleKind
+module.exports.Severity = __napiModule.exports.Severity
 module.exports.typescript = __napiModule.exports.typescript
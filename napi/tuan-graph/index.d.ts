@@ -4,8 +4,96 @@ export declare class Graph {
   positioning(): void
   describe(): GraphDescription
   clusterize(maxIters: number): Array<Cluster>
+  /**
+   * Serializes the graph for external tools. Pass the result of `clusterize` to include
+   * cluster membership.
+   */
+  export(format: ExportFormat, clusters?: Array<Cluster> | undefined | null): string
+  /**
+   * Files imported by `id`. `maxDepth` of 1 only returns direct imports, omit it to follow
+   * imports transitively.
+   */
+  dependencies(id: number, maxDepth?: number | undefined | null): Array<number>
+  /**
+   * Files importing `id`. `maxDepth` of 1 only returns direct importers, omit it to follow
+   * importers transitively.
+   */
+  dependents(id: number, maxDepth?: number | undefined | null): Array<number>
+  /** Shortest chain of imports leading from `from` to `to`, both included. */
+  shortestPath(from: number, to: number): Array<number> | null
+  /** Every chain of imports from `from` to `to` going through at most `maxLength` edges. */
+  simplePaths(from: number, to: number, maxLength: number): Array<Array<number>>
+  /** Whether `to` is imported by `from`, directly or transitively. */
+  isReachable(from: number, to: number): boolean
+  /**
+   * Import cycles, one per strongly connected component, each with a short representative
+   * cycle and the import edges it goes through.
+   */
+  cycles(options?: CycleOptions | undefined | null): Array<Cycle>
+  /**
+   * Files, imports and cycles added or removed in `other`, a newer graph of the same project.
+   * Files are matched by project-relative path.
+   */
+  compare(other: Graph, cycleOptions?: CycleOptions | undefined | null): GraphComparison
+  /**
+   * Flags the files that no entry point leads to and returns them. Entry points are read from
+   * every `package.json` and `index.html`, plus the files matching `entryGlobs`.
+   */
+  deadFiles(entryGlobs?: Array<string> | undefined | null): Array<number>
+  /**
+   * Files affected by a change of `changedPaths`, e.g. the output of `git diff --name-only`,
+   * and the tests among them. Relative paths are resolved against the project root.
+   * `testPatterns` defaults to `*.test.*`, `*.spec.*` and `__tests__/` files.
+   */
+  impact(changedPaths: Array<string>, testPatterns?: Array<string> | undefined | null): Impact
+  /** Centrality metrics of every file, e.g. to colour a heatmap. */
+  metrics(): Array<NodeMetrics>
+  /**
+   * Imports breaking the architecture rules of `rulesPath`, which defaults to
+   * `tuan.rules.json` in the project root.
+   */
+  checkRules(rulesPath?: string | undefined | null): Array<RuleViolation>
+  /**
+   * Unresolved imports, parse errors and unreadable files found while building the graph,
+   * ordered by file and location.
+   */
+  diagnostics(): Array<Diagnostic>
+  /**
+   * The graph aggregated by workspace package, each file belonging to the package of its
+   * nearest enclosing `package.json`.
+   */
+  packageGraph(): PackageGraph
+  /**
+   * The directory hierarchy of the graph, with directories that can be collapsed into a single
+   * node.
+   */
+  compound(): CompoundGraph
   get nodes(): Array<Node>
   get edges(): Array<Edge>
+  /**
+   * Watches the project this graph was built from. The graph is patched in place and every
+   * change is reported to `callback`. A graph can only be watched once.
+   */
+  watch(callback: (arg: GraphDiff) => void, debounceMs?: number | undefined | null): GraphWatcher
+}
+
+/**
+ * Directories have the id `dir:<project-relative path>` (`dir:` for the project root), files
+ * have the id `file:<node id>`.
+ */
+export declare class CompoundGraph {
+  /** Returns `false` if `directory` is not part of the hierarchy. */
+  collapse(directory: string): boolean
+  /** Returns `false` if `directory` was not collapsed. */
+  expand(directory: string): boolean
+  /** Collapses every directory `depth` levels below the root. */
+  collapseToDepth(depth: number): void
+  expandAll(): void
+  view(): CompoundView
+}
+
+export declare class GraphWatcher {
+  close(): void
 }
 
 export interface Cluster {
@@ -13,9 +101,94 @@ export interface Cluster {
   members: Array<number>
 }
 
+export interface CompoundEdge {
+  from: string
+  to: string
+  /** Number of file-level edges this edge stands for. */
+  count: number
+}
+
+export interface CompoundNode {
+  id: string
+  /** Node id of files, unset for directories. */
+  fileId?: number
+  parent?: string
+  label: string
+  collapsed: boolean
+  /** Number of files in this directory and its subdirectories. */
+  fileCount: number
+}
+
+export interface CompoundView {
+  nodes: Array<CompoundNode>
+  edges: Array<CompoundEdge>
+}
+
+export interface Cycle {
+  members: Array<number>
+  path: Array<number>
+  edges: Array<Edge>
+}
+
+export interface CycleOptions {
+  /** Skip `import type` edges, which are erased at runtime. */
+  ignoreTypeOnly?: boolean
+  /** Skip `import()` edges, which are only evaluated once the module finished loading. */
+  ignoreDynamic?: boolean
+}
+
+export interface Diagnostic {
+  kind: DiagnosticKind
+  severity: Severity
+  filePath: string
+  location?: SourceLocation
+  /** The import specifier, for unresolved imports. */
+  specifier?: string
+  /** The resolver, parser or I/O error. */
+  message: string
+}
+
+export declare enum DiagnosticKind {
+  UnresolvedImport = 'unresolved-import',
+  ParseError = 'parse-error',
+  ReadError = 'read-error'
+}
+
 export interface Edge {
   from: number
   to: number
+  kind: EdgeKind
+  symbols: Array<string>
+  location?: SourceLocation
+  /** Query string or fragment of the import specifier, e.g. `?worker`. */
+  suffix?: string
+}
+
+export declare enum EdgeKind {
+  Static = 'static',
+  Dynamic = 'dynamic',
+  Require = 'require',
+  ReExport = 're-export',
+  TypeOnly = 'type-only',
+  SideEffect = 'side-effect',
+  Worker = 'worker',
+  Asset = 'asset'
+}
+
+export declare enum ExportFormat {
+  Dot = 'dot',
+  GraphMl = 'graphml',
+  Gexf = 'gexf',
+  JsonGraph = 'jgf'
+}
+
+export interface GraphComparison {
+  addedFiles: Array<string>
+  removedFiles: Array<string>
+  addedEdges: Array<PathEdge>
+  removedEdges: Array<PathEdge>
+  /** Representative path of every cycle whose files did not already form a cycle before. */
+  newCycles: Array<Array<string>>
 }
 
 export interface GraphDescription {
@@ -23,13 +196,146 @@ export interface GraphDescription {
   edges: Array<Edge>
 }
 
+export interface GraphDiff {
+  addedNodes: Array<Node>
+  removedNodes: Array<Node>
+  addedEdges: Array<Edge>
+  removedEdges: Array<Edge>
+}
+
+export interface Impact {
+  changed: Array<number>
+  affected: Array<number>
+  tests: Array<number>
+}
+
 export interface Node {
   id: number
   label: string
   filePath: string
   position: [number, number]
+  dead: boolean
+  kind: NodeKind
+}
+
+export declare enum NodeKind {
+  Source = 'source',
+  ExternalPackage = 'external-package',
+  Stylesheet = 'stylesheet',
+  Asset = 'asset'
+}
+
+export interface NodeMetrics {
+  id: number
+  /** Number of files importing this one (afferent coupling, Ca). */
+  fanIn: number
+  /** Number of files imported by this one (efferent coupling, Ce). */
+  fanOut: number
+  /** Sums to 1 over the graph. Heavily imported files rank higher. */
+  pageRank: number
+  /** Share of shortest import chains going through this file, between 0 and 1. */
+  betweenness: number
+  /** Length of the longest import chain starting at this file. */
+  depth: number
+  /** `Ce / (Ca + Ce)`: 0 for files that only get imported, 1 for files that only import. */
+  instability: number
+}
+
+export interface Package {
+  id: number
+  name: string
+  root: string
+  members: Array<number>
+}
+
+export interface PackageEdge {
+  from: number
+  to: number
+  count: number
+  /** The file-level imports from `from` to `to`, for drilling down. */
+  edges: Array<Edge>
+}
+
+export interface PackageGraph {
+  packages: Array<Package>
+  edges: Array<PackageEdge>
+}
+
+/** An import between two project-relative paths. */
+export interface PathEdge {
+  from: string
+  to: string
+  kind: EdgeKind
+}
+
+export declare enum RuleKind {
+  Deny = 'deny',
+  Allow = 'allow'
+}
+
+export interface RuleViolation {
+  rule: string
+  kind: RuleKind
+  /** The offending import. Its `location` points at the import in the importing file. */
+  edge: Edge
+  fromPath: string
+  toPath: string
+}
+
+export declare enum Severity {
+  Error = 'error',
+  Warning = 'warning'
+}
+
+export interface SourceLocation {
+  start: number
+  end: number
+  line: number
+  column: number
 }
 
 export declare namespace typescript {
-  export function getGraph(projectPath: string): Graph
+  /**
+   * When `cacheDir` is given (e.g. `<projectPath>/.tuan`), imports of unchanged files are
+   * read from the cache stored there instead of being parsed again.
+   */
+  export function getGraph(projectPath: string, cacheDir?: string | undefined | null, options?: TypescriptOptions | undefined | null): Graph
+  /**
+   * Builds the graph of `projectPath` at the `base` and `head` git revisions, each checked out
+   * in a temporary worktree, and compares them.
+   */
+  export function compareRevisions(projectPath: string, base: string, head: string, options?: TypescriptOptions | undefined | null, cycleOptions?: CycleOptions | undefined | null): GraphComparison
+  export interface TypescriptOptions {
+    /**
+     * Only files whose project-relative path matches one of these globs are part of the
+     * graph. Defaults to every file.
+     */
+    include?: Array<string>
+    /**
+     * Files and directories whose project-relative path matches one of these globs are
+     * skipped. Defaults to `dist`, `build`, `out`, `coverage`, `.next`, `.svelte-kit`, `.astro`
+     * and `storybook-static` directories. `node_modules` and `.git` are always skipped.
+     */
+    exclude?: Array<string>
+    /**
+     * Extensions parsed on top of `ts`, `tsx`, `js`, `jsx`, `mjs`, `cjs`, `vue`, `svelte` and
+     * `astro`, e.g. `mts`.
+     */
+    extensions?: Array<string>
+    /** Files larger than this many bytes are skipped, e.g. bundled vendor files. */
+    maxFileSize?: number
+    followSymlinks?: boolean
+    /** Whether `.d.ts` files are part of the graph. Defaults to `true`. */
+    includeDeclarations?: boolean
+    /**
+     * Adds a node per npm package imported from `node_modules`, instead of dropping those
+     * imports.
+     */
+    externalPackages?: boolean
+    /**
+     * Adds stylesheets to the graph along with their `@import`, `@use`, `@forward` and `url()`
+     * references, and a node per imported image, font, JSON file or other non-code file.
+     */
+    assets?: boolean
+  }
 }
@@ -573,4 +573,12 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.Graph = nativeBinding.Graph
+module.exports.CompoundGraph = nativeBinding.CompoundGraph
+module.exports.GraphWatcher = nativeBinding.GraphWatcher
+module.exports.DiagnosticKind = nativeBinding.DiagnosticKind
+module.exports.EdgeKind = nativeBinding.EdgeKind
+module.exports.ExportFormat = nativeBinding.ExportFormat
+module.exports.NodeKind = nativeBinding.NodeKind
+module.exports.RuleKind = nativeBinding.RuleKind
+module.exports.Severity = nativeBinding.Severity
 module.exports.typescript = nativeBinding.typescript
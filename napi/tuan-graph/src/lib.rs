@@ -1,11 +1,67 @@
 #![deny(clippy::all)]
 
+use napi::{
+  threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
+  Status,
+};
 use napi_derive::napi;
-use tuan_graph::{cluster, graph};
+use std::{
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard},
+  time::Duration,
+};
+use tuan_graph::{
+  cluster, compare, cycles, diagnostics, export, graph, graph_builders, hierarchy, metrics,
+  packages, rules, watch,
+};
 
 #[napi(js_name = "Graph")]
 pub struct Graph {
-  pub(crate) inner: graph::Graph,
+  pub(crate) inner: Arc<RwLock<graph::Graph>>,
+  /// Shared with the watcher, which locks it while applying updates.
+  pub(crate) builder: Option<Arc<Mutex<graph_builders::Typescript>>>,
+  pub(crate) project_root: Option<PathBuf>,
+  pub(crate) watched: bool,
+}
+
+impl Graph {
+  pub(crate) fn new(inner: graph::Graph, builder: Option<graph_builders::Typescript>) -> Self {
+    Self {
+      inner: Arc::new(RwLock::new(inner)),
+      project_root: builder
+        .as_ref()
+        .map(|builder| builder.project_root().to_path_buf()),
+      builder: builder.map(|builder| Arc::new(Mutex::new(builder))),
+      watched: false,
+    }
+  }
+
+  fn graph(&self) -> RwLockReadGuard<'_, graph::Graph> {
+    self.inner.read().unwrap()
+  }
+
+  fn builder(&self) -> napi::Result<MutexGuard<'_, graph_builders::Typescript>> {
+    self
+      .builder
+      .as_ref()
+      .map(|builder| builder.lock().unwrap())
+      .ok_or_else(not_built_from_project)
+  }
+
+  fn project_root(&self) -> napi::Result<&Path> {
+    self
+      .project_root
+      .as_deref()
+      .ok_or_else(not_built_from_project)
+  }
+}
+
+fn not_built_from_project() -> napi::Error {
+  napi::Error::from_reason("This graph was not built from a project")
+}
+
+fn to_ids(ids: Vec<graph::NodeId>) -> Vec<i64> {
+  ids.into_iter().map(|id| id as i64).collect()
 }
 
 #[napi(object)]
@@ -18,7 +74,7 @@ pub struct GraphDescription {
 impl Graph {
   #[napi]
   pub fn positioning(&mut self) {
-    self.inner.positioning();
+    self.inner.write().unwrap().positioning();
   }
 
   #[napi]
@@ -32,17 +88,203 @@ impl Graph {
   #[napi]
   pub fn clusterize(&self, max_iters: u32) -> Vec<Cluster> {
     self
-      .inner
+      .graph()
       .clusterize(max_iters as usize)
       .into_iter()
       .map(Cluster::from_native)
       .collect()
   }
 
+  /// Serializes the graph for external tools. Pass the result of `clusterize` to include
+  /// cluster membership.
+  #[napi]
+  pub fn export(&self, format: ExportFormat, clusters: Option<Vec<Cluster>>) -> String {
+    let clusters = clusters.map(|clusters| {
+      clusters
+        .into_iter()
+        .map(Cluster::into_native)
+        .collect::<Vec<_>>()
+    });
+    self.graph().export(format.into(), clusters.as_deref())
+  }
+
+  /// Files imported by `id`. `maxDepth` of 1 only returns direct imports, omit it to follow
+  /// imports transitively.
+  #[napi]
+  pub fn dependencies(&self, id: i64, max_depth: Option<u32>) -> Vec<i64> {
+    to_ids(
+      self
+        .graph()
+        .dependencies(id as u64, max_depth.map(|depth| depth as usize)),
+    )
+  }
+
+  /// Files importing `id`. `maxDepth` of 1 only returns direct importers, omit it to follow
+  /// importers transitively.
+  #[napi]
+  pub fn dependents(&self, id: i64, max_depth: Option<u32>) -> Vec<i64> {
+    to_ids(
+      self
+        .graph()
+        .dependents(id as u64, max_depth.map(|depth| depth as usize)),
+    )
+  }
+
+  /// Shortest chain of imports leading from `from` to `to`, both included.
+  #[napi]
+  pub fn shortest_path(&self, from: i64, to: i64) -> Option<Vec<i64>> {
+    self
+      .graph()
+      .shortest_path(from as u64, to as u64)
+      .map(to_ids)
+  }
+
+  /// Every chain of imports from `from` to `to` going through at most `maxLength` edges.
+  #[napi]
+  pub fn simple_paths(&self, from: i64, to: i64, max_length: u32) -> Vec<Vec<i64>> {
+    self
+      .graph()
+      .simple_paths(from as u64, to as u64, max_length as usize)
+      .into_iter()
+      .map(to_ids)
+      .collect()
+  }
+
+  /// Whether `to` is imported by `from`, directly or transitively.
+  #[napi]
+  pub fn is_reachable(&self, from: i64, to: i64) -> bool {
+    self.graph().is_reachable(from as u64, to as u64)
+  }
+
+  /// Import cycles, one per strongly connected component, each with a short representative
+  /// cycle and the import edges it goes through.
+  #[napi]
+  pub fn cycles(&self, options: Option<CycleOptions>) -> Vec<Cycle> {
+    self
+      .graph()
+      .cycles(options.map(CycleOptions::into_native).unwrap_or_default())
+      .into_iter()
+      .map(Cycle::from_native)
+      .collect()
+  }
+
+  /// Files, imports and cycles added or removed in `other`, a newer graph of the same project.
+  /// Files are matched by project-relative path.
+  #[napi]
+  pub fn compare(
+    &self,
+    other: &Graph,
+    cycle_options: Option<CycleOptions>,
+  ) -> napi::Result<GraphComparison> {
+    let comparison = self.graph().compare(
+      self.project_root()?,
+      &other.graph(),
+      other.project_root()?,
+      cycle_options
+        .map(CycleOptions::into_native)
+        .unwrap_or_default(),
+    );
+    Ok(GraphComparison::from_native(comparison))
+  }
+
+  /// Flags the files that no entry point leads to and returns them. Entry points are read from
+  /// every `package.json` and `index.html`, plus the files matching `entryGlobs`.
+  #[napi]
+  pub fn dead_files(&self, entry_globs: Option<Vec<String>>) -> napi::Result<Vec<i64>> {
+    let entry_points = self
+      .builder()?
+      .entry_points(&entry_globs.unwrap_or_default())
+      .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    Ok(to_ids(
+      self.inner.write().unwrap().mark_unreachable(&entry_points),
+    ))
+  }
+
+  /// Files affected by a change of `changedPaths`, e.g. the output of `git diff --name-only`,
+  /// and the tests among them. Relative paths are resolved against the project root.
+  /// `testPatterns` defaults to `*.test.*`, `*.spec.*` and `__tests__/` files.
+  #[napi]
+  pub fn impact(
+    &self,
+    changed_paths: Vec<String>,
+    test_patterns: Option<Vec<String>>,
+  ) -> napi::Result<Impact> {
+    let builder = self.builder()?;
+    let paths = changed_paths.into_iter().map(PathBuf::from);
+    let impact = match test_patterns {
+      Some(test_patterns) => builder.impact(paths, &test_patterns),
+      None => builder.impact(paths, &graph_builders::DEFAULT_TEST_PATTERNS),
+    }
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    Ok(Impact::from_native(impact))
+  }
+
+  /// Centrality metrics of every file, e.g. to colour a heatmap.
+  #[napi]
+  pub fn metrics(&self) -> Vec<NodeMetrics> {
+    let mut metrics = self
+      .graph()
+      .metrics()
+      .into_iter()
+      .map(|(id, metrics)| NodeMetrics::from_native(id, metrics))
+      .collect::<Vec<_>>();
+    metrics.sort_by_key(|metrics| metrics.id);
+    metrics
+  }
+
+  /// Imports breaking the architecture rules of `rulesPath`, which defaults to
+  /// `tuan.rules.json` in the project root.
+  #[napi]
+  pub fn check_rules(&self, rules_path: Option<String>) -> napi::Result<Vec<RuleViolation>> {
+    let project_root = self.project_root()?;
+    let rules_path = project_root.join(rules_path.as_deref().unwrap_or(rules::DEFAULT_RULES_FILE));
+    let rules =
+      rules::RuleSet::load(&rules_path).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    Ok(
+      self
+        .graph()
+        .check_rules(&rules, project_root)
+        .into_iter()
+        .map(RuleViolation::from_native)
+        .collect(),
+    )
+  }
+
+  /// Unresolved imports, parse errors and unreadable files found while building the graph,
+  /// ordered by file and location.
+  #[napi]
+  pub fn diagnostics(&self) -> napi::Result<Vec<Diagnostic>> {
+    Ok(
+      self
+        .builder()?
+        .diagnostics()
+        .into_iter()
+        .map(Diagnostic::from_native)
+        .collect(),
+    )
+  }
+
+  /// The graph aggregated by workspace package, each file belonging to the package of its
+  /// nearest enclosing `package.json`.
+  #[napi]
+  pub fn package_graph(&self) -> napi::Result<PackageGraph> {
+    Ok(PackageGraph::from_native(self.builder()?.package_graph()))
+  }
+
+  /// The directory hierarchy of the graph, with directories that can be collapsed into a single
+  /// node.
+  #[napi]
+  pub fn compound(&self) -> napi::Result<CompoundGraph> {
+    let project_root = self.project_root()?;
+    Ok(CompoundGraph {
+      inner: hierarchy::CompoundGraph::new(&self.graph(), project_root),
+    })
+  }
+
   #[napi(getter)]
   pub fn nodes(&self) -> Vec<Node> {
     self
-      .inner
+      .graph()
       .iter_nodes()
       .cloned()
       .map(Node::from_native)
@@ -52,44 +294,433 @@ impl Graph {
   #[napi(getter)]
   pub fn edges(&self) -> Vec<Edge> {
     self
-      .inner
+      .graph()
       .iter_edges()
       .cloned()
       .map(Edge::from_native)
       .collect()
   }
+
+  /// Watches the project this graph was built from. The graph is patched in place and every
+  /// change is reported to `callback`. A graph can only be watched once.
+  #[napi]
+  pub fn watch(
+    &mut self,
+    callback: ThreadsafeFunction<GraphDiff, (), GraphDiff, Status, false>,
+    debounce_ms: Option<u32>,
+  ) -> napi::Result<GraphWatcher> {
+    if self.watched {
+      return Err(napi::Error::from_reason("This graph is already watched"));
+    }
+    let builder = self.builder.clone().ok_or_else(not_built_from_project)?;
+    let inner = self.inner.clone();
+    let debounce = Duration::from_millis(debounce_ms.unwrap_or(200) as u64);
+
+    let watcher = watch::Watcher::new(builder, debounce, move |diff| {
+      inner.write().unwrap().apply(diff);
+      callback.call(
+        GraphDiff::from_native(diff.clone()),
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
+    })
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    self.watched = true;
+
+    Ok(GraphWatcher {
+      inner: Some(watcher),
+    })
+  }
+}
+
+#[napi]
+pub struct GraphWatcher {
+  inner: Option<watch::Watcher>,
+}
+
+#[napi]
+impl GraphWatcher {
+  #[napi]
+  pub fn close(&mut self) {
+    if let Some(watcher) = self.inner.take() {
+      watcher.stop();
+    }
+  }
+}
+
+/// Directories have the id `dir:<project-relative path>` (`dir:` for the project root), files
+/// have the id `file:<node id>`.
+#[napi]
+pub struct CompoundGraph {
+  inner: hierarchy::CompoundGraph,
+}
+
+#[napi]
+impl CompoundGraph {
+  /// Returns `false` if `directory` is not part of the hierarchy.
+  #[napi]
+  pub fn collapse(&mut self, directory: String) -> bool {
+    self.inner.collapse(&directory)
+  }
+
+  /// Returns `false` if `directory` was not collapsed.
+  #[napi]
+  pub fn expand(&mut self, directory: String) -> bool {
+    self.inner.expand(&directory)
+  }
+
+  /// Collapses every directory `depth` levels below the root.
+  #[napi]
+  pub fn collapse_to_depth(&mut self, depth: u32) {
+    self.inner.collapse_to_depth(depth as usize);
+  }
+
+  #[napi]
+  pub fn expand_all(&mut self) {
+    self.inner.expand_all();
+  }
+
+  #[napi]
+  pub fn view(&self) -> CompoundView {
+    let view = self.inner.view();
+    CompoundView {
+      nodes: view
+        .nodes
+        .into_iter()
+        .map(|node| CompoundNode {
+          id: compound_id(&node.id),
+          file_id: match node.id {
+            hierarchy::CompoundNodeId::File(id) => Some(id as i64),
+            hierarchy::CompoundNodeId::Directory(_) => None,
+          },
+          parent: node
+            .parent
+            .map(|parent| compound_id(&hierarchy::CompoundNodeId::Directory(parent))),
+          label: node.label,
+          collapsed: node.collapsed,
+          file_count: node.file_count as u32,
+        })
+        .collect(),
+      edges: view
+        .edges
+        .into_iter()
+        .map(|edge| CompoundEdge {
+          from: compound_id(&edge.from),
+          to: compound_id(&edge.to),
+          count: edge.count as u32,
+        })
+        .collect(),
+    }
+  }
+}
+
+fn compound_id(id: &hierarchy::CompoundNodeId) -> String {
+  match id {
+    hierarchy::CompoundNodeId::Directory(path) => format!("dir:{path}"),
+    hierarchy::CompoundNodeId::File(id) => format!("file:{id}"),
+  }
+}
+
+#[napi(object)]
+pub struct CompoundView {
+  pub nodes: Vec<CompoundNode>,
+  pub edges: Vec<CompoundEdge>,
+}
+
+#[napi(object)]
+pub struct CompoundNode {
+  pub id: String,
+  /// Node id of files, unset for directories.
+  pub file_id: Option<i64>,
+  pub parent: Option<String>,
+  pub label: String,
+  pub collapsed: bool,
+  /// Number of files in this directory and its subdirectories.
+  pub file_count: u32,
+}
+
+#[napi(object)]
+pub struct CompoundEdge {
+  pub from: String,
+  pub to: String,
+  /// Number of file-level edges this edge stands for.
+  pub count: u32,
+}
+
+#[napi(object)]
+pub struct GraphDiff {
+  pub added_nodes: Vec<Node>,
+  pub removed_nodes: Vec<Node>,
+  pub added_edges: Vec<Edge>,
+  pub removed_edges: Vec<Edge>,
+}
+
+impl GraphDiff {
+  pub(crate) fn from_native(diff: graph::GraphDiff) -> Self {
+    Self {
+      added_nodes: diff
+        .added_nodes
+        .into_iter()
+        .map(Node::from_native)
+        .collect(),
+      removed_nodes: diff
+        .removed_nodes
+        .into_iter()
+        .map(Node::from_native)
+        .collect(),
+      added_edges: diff
+        .added_edges
+        .into_iter()
+        .map(Edge::from_native)
+        .collect(),
+      removed_edges: diff
+        .removed_edges
+        .into_iter()
+        .map(Edge::from_native)
+        .collect(),
+    }
+  }
 }
 
 #[napi(object)]
 pub struct Node {
-  pub id: u32,
+  pub id: i64,
   pub label: String,
   pub file_path: String,
   pub position: (f64, f64),
+  pub dead: bool,
+  pub kind: NodeKind,
 }
 
 impl Node {
   pub(crate) fn from_native(node: graph::Node) -> Self {
     Self {
-      id: node.id as u32,
+      id: node.id as i64,
       label: node.label,
       file_path: node.file_path.to_string_lossy().to_string(),
       position: (node.position.0.into(), node.position.1.into()),
+      dead: node.dead,
+      kind: node.kind.into(),
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum NodeKind {
+  #[napi(value = "source")]
+  Source,
+  #[napi(value = "external-package")]
+  ExternalPackage,
+  #[napi(value = "stylesheet")]
+  Stylesheet,
+  #[napi(value = "asset")]
+  Asset,
+}
+
+impl From<graph::NodeKind> for NodeKind {
+  fn from(kind: graph::NodeKind) -> Self {
+    match kind {
+      graph::NodeKind::Source => NodeKind::Source,
+      graph::NodeKind::ExternalPackage => NodeKind::ExternalPackage,
+      graph::NodeKind::Stylesheet => NodeKind::Stylesheet,
+      graph::NodeKind::Asset => NodeKind::Asset,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct NodeMetrics {
+  pub id: i64,
+  /// Number of files importing this one (afferent coupling, Ca).
+  pub fan_in: u32,
+  /// Number of files imported by this one (efferent coupling, Ce).
+  pub fan_out: u32,
+  /// Sums to 1 over the graph. Heavily imported files rank higher.
+  pub page_rank: f64,
+  /// Share of shortest import chains going through this file, between 0 and 1.
+  pub betweenness: f64,
+  /// Length of the longest import chain starting at this file.
+  pub depth: u32,
+  /// `Ce / (Ca + Ce)`: 0 for files that only get imported, 1 for files that only import.
+  pub instability: f64,
+}
+
+impl NodeMetrics {
+  pub(crate) fn from_native(id: graph::NodeId, metrics: metrics::NodeMetrics) -> Self {
+    Self {
+      id: id as i64,
+      fan_in: metrics.fan_in as u32,
+      fan_out: metrics.fan_out as u32,
+      page_rank: metrics.page_rank,
+      betweenness: metrics.betweenness,
+      depth: metrics.depth as u32,
+      instability: metrics.instability,
     }
   }
 }
 
 #[napi(object)]
 pub struct Edge {
-  pub from: u32,
-  pub to: u32,
+  pub from: i64,
+  pub to: i64,
+  pub kind: EdgeKind,
+  pub symbols: Vec<String>,
+  pub location: Option<SourceLocation>,
+  /// Query string or fragment of the import specifier, e.g. `?worker`.
+  pub suffix: Option<String>,
 }
 
 impl Edge {
   pub fn from_native(edge: graph::Edge) -> Self {
     Self {
-      from: edge.from as u32,
-      to: edge.to as u32,
+      from: edge.from as i64,
+      to: edge.to as i64,
+      kind: edge.kind.into(),
+      symbols: edge.symbols,
+      location: edge.location.map(SourceLocation::from_native),
+      suffix: edge.suffix,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct PackageGraph {
+  pub packages: Vec<Package>,
+  pub edges: Vec<PackageEdge>,
+}
+
+impl PackageGraph {
+  pub(crate) fn from_native(package_graph: packages::PackageGraph) -> Self {
+    Self {
+      packages: package_graph
+        .packages
+        .into_iter()
+        .map(|package| Package {
+          id: package.id as u32,
+          name: package.name,
+          root: package.root.to_string_lossy().to_string(),
+          members: to_ids(package.members),
+        })
+        .collect(),
+      edges: package_graph
+        .edges
+        .into_iter()
+        .map(|edge| PackageEdge {
+          from: edge.from as u32,
+          to: edge.to as u32,
+          count: edge.count() as u32,
+          edges: edge.edges.into_iter().map(Edge::from_native).collect(),
+        })
+        .collect(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct Package {
+  pub id: u32,
+  pub name: String,
+  pub root: String,
+  pub members: Vec<i64>,
+}
+
+#[napi(object)]
+pub struct PackageEdge {
+  pub from: u32,
+  pub to: u32,
+  pub count: u32,
+  /// The file-level imports from `from` to `to`, for drilling down.
+  pub edges: Vec<Edge>,
+}
+
+#[napi(object)]
+pub struct RuleViolation {
+  pub rule: String,
+  pub kind: RuleKind,
+  /// The offending import. Its `location` points at the import in the importing file.
+  pub edge: Edge,
+  pub from_path: String,
+  pub to_path: String,
+}
+
+impl RuleViolation {
+  pub(crate) fn from_native(violation: rules::Violation) -> Self {
+    Self {
+      rule: violation.rule,
+      kind: violation.kind.into(),
+      edge: Edge::from_native(violation.edge),
+      from_path: violation.from_path.to_string_lossy().to_string(),
+      to_path: violation.to_path.to_string_lossy().to_string(),
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum RuleKind {
+  #[napi(value = "deny")]
+  Deny,
+  #[napi(value = "allow")]
+  Allow,
+}
+
+impl From<rules::RuleKind> for RuleKind {
+  fn from(kind: rules::RuleKind) -> Self {
+    match kind {
+      rules::RuleKind::Deny => RuleKind::Deny,
+      rules::RuleKind::Allow => RuleKind::Allow,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct SourceLocation {
+  pub start: u32,
+  pub end: u32,
+  pub line: u32,
+  pub column: u32,
+}
+
+impl SourceLocation {
+  pub(crate) fn from_native(location: graph::SourceLocation) -> Self {
+    Self {
+      start: location.start,
+      end: location.end,
+      line: location.line,
+      column: location.column,
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum EdgeKind {
+  #[napi(value = "static")]
+  Static,
+  #[napi(value = "dynamic")]
+  Dynamic,
+  #[napi(value = "require")]
+  Require,
+  #[napi(value = "re-export")]
+  ReExport,
+  #[napi(value = "type-only")]
+  TypeOnly,
+  #[napi(value = "side-effect")]
+  SideEffect,
+  #[napi(value = "worker")]
+  Worker,
+  #[napi(value = "asset")]
+  Asset,
+}
+
+impl From<graph::EdgeKind> for EdgeKind {
+  fn from(kind: graph::EdgeKind) -> Self {
+    match kind {
+      graph::EdgeKind::Static => EdgeKind::Static,
+      graph::EdgeKind::Dynamic => EdgeKind::Dynamic,
+      graph::EdgeKind::Require => EdgeKind::Require,
+      graph::EdgeKind::ReExport => EdgeKind::ReExport,
+      graph::EdgeKind::TypeOnly => EdgeKind::TypeOnly,
+      graph::EdgeKind::SideEffect => EdgeKind::SideEffect,
+      graph::EdgeKind::Worker => EdgeKind::Worker,
+      graph::EdgeKind::Asset => EdgeKind::Asset,
     }
   }
 }
@@ -97,29 +728,304 @@ impl Edge {
 #[napi(object)]
 pub struct Cluster {
   pub id: u32,
-  pub members: Vec<u32>,
+  pub members: Vec<i64>,
 }
 
 impl Cluster {
   pub(crate) fn from_native(cluster: cluster::Cluster) -> Self {
     Self {
       id: cluster.id as u32,
-      members: cluster.members.iter().map(|&id| id as u32).collect(),
+      members: cluster.members.iter().map(|&id| id as i64).collect(),
+    }
+  }
+
+  pub(crate) fn into_native(self) -> cluster::Cluster {
+    cluster::Cluster {
+      id: self.id as usize,
+      members: self.members.into_iter().map(|id| id as u64).collect(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct Impact {
+  pub changed: Vec<i64>,
+  pub affected: Vec<i64>,
+  pub tests: Vec<i64>,
+}
+
+impl Impact {
+  pub(crate) fn from_native(impact: graph_builders::Impact) -> Self {
+    Self {
+      changed: to_ids(impact.changed),
+      affected: to_ids(impact.affected),
+      tests: to_ids(impact.tests),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct CycleOptions {
+  /// Skip `import type` edges, which are erased at runtime.
+  pub ignore_type_only: Option<bool>,
+  /// Skip `import()` edges, which are only evaluated once the module finished loading.
+  pub ignore_dynamic: Option<bool>,
+}
+
+impl CycleOptions {
+  pub(crate) fn into_native(self) -> cycles::CycleOptions {
+    cycles::CycleOptions {
+      ignore_type_only: self.ignore_type_only.unwrap_or(false),
+      ignore_dynamic: self.ignore_dynamic.unwrap_or(false),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct Cycle {
+  pub members: Vec<i64>,
+  pub path: Vec<i64>,
+  pub edges: Vec<Edge>,
+}
+
+impl Cycle {
+  pub(crate) fn from_native(cycle: cycles::Cycle) -> Self {
+    Self {
+      members: to_ids(cycle.members),
+      path: to_ids(cycle.path),
+      edges: cycle.edges.into_iter().map(Edge::from_native).collect(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct GraphComparison {
+  pub added_files: Vec<String>,
+  pub removed_files: Vec<String>,
+  pub added_edges: Vec<PathEdge>,
+  pub removed_edges: Vec<PathEdge>,
+  /// Representative path of every cycle whose files did not already form a cycle before.
+  pub new_cycles: Vec<Vec<String>>,
+}
+
+impl GraphComparison {
+  pub(crate) fn from_native(comparison: compare::GraphComparison) -> Self {
+    let to_strings = |paths: Vec<PathBuf>| -> Vec<String> {
+      paths
+        .into_iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+    };
+    Self {
+      added_files: to_strings(comparison.added_files),
+      removed_files: to_strings(comparison.removed_files),
+      added_edges: comparison
+        .added_edges
+        .into_iter()
+        .map(PathEdge::from_native)
+        .collect(),
+      removed_edges: comparison
+        .removed_edges
+        .into_iter()
+        .map(PathEdge::from_native)
+        .collect(),
+      new_cycles: comparison.new_cycles.into_iter().map(to_strings).collect(),
+    }
+  }
+}
+
+/// An import between two project-relative paths.
+#[napi(object)]
+pub struct PathEdge {
+  pub from: String,
+  pub to: String,
+  pub kind: EdgeKind,
+}
+
+impl PathEdge {
+  pub(crate) fn from_native(edge: compare::PathEdge) -> Self {
+    Self {
+      from: edge.from.to_string_lossy().to_string(),
+      to: edge.to.to_string_lossy().to_string(),
+      kind: edge.kind.into(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct Diagnostic {
+  pub kind: DiagnosticKind,
+  pub severity: Severity,
+  pub file_path: String,
+  pub location: Option<SourceLocation>,
+  /// The import specifier, for unresolved imports.
+  pub specifier: Option<String>,
+  /// The resolver, parser or I/O error.
+  pub message: String,
+}
+
+impl Diagnostic {
+  pub(crate) fn from_native(diagnostic: diagnostics::Diagnostic) -> Self {
+    Self {
+      kind: diagnostic.kind.into(),
+      severity: diagnostic.severity.into(),
+      file_path: diagnostic.file_path.to_string_lossy().to_string(),
+      location: diagnostic.location.map(SourceLocation::from_native),
+      specifier: diagnostic.specifier,
+      message: diagnostic.message,
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum DiagnosticKind {
+  #[napi(value = "unresolved-import")]
+  UnresolvedImport,
+  #[napi(value = "parse-error")]
+  ParseError,
+  #[napi(value = "read-error")]
+  ReadError,
+}
+
+impl From<diagnostics::DiagnosticKind> for DiagnosticKind {
+  fn from(kind: diagnostics::DiagnosticKind) -> Self {
+    match kind {
+      diagnostics::DiagnosticKind::UnresolvedImport => DiagnosticKind::UnresolvedImport,
+      diagnostics::DiagnosticKind::ParseError => DiagnosticKind::ParseError,
+      diagnostics::DiagnosticKind::ReadError => DiagnosticKind::ReadError,
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum Severity {
+  #[napi(value = "error")]
+  Error,
+  #[napi(value = "warning")]
+  Warning,
+}
+
+impl From<diagnostics::Severity> for Severity {
+  fn from(severity: diagnostics::Severity) -> Self {
+    match severity {
+      diagnostics::Severity::Error => Severity::Error,
+      diagnostics::Severity::Warning => Severity::Warning,
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum ExportFormat {
+  #[napi(value = "dot")]
+  Dot,
+  #[napi(value = "graphml")]
+  GraphMl,
+  #[napi(value = "gexf")]
+  Gexf,
+  #[napi(value = "jgf")]
+  JsonGraph,
+}
+
+impl From<ExportFormat> for export::ExportFormat {
+  fn from(format: ExportFormat) -> Self {
+    match format {
+      ExportFormat::Dot => export::ExportFormat::Dot,
+      ExportFormat::GraphMl => export::ExportFormat::GraphMl,
+      ExportFormat::Gexf => export::ExportFormat::Gexf,
+      ExportFormat::JsonGraph => export::ExportFormat::JsonGraph,
     }
   }
 }
 
 #[napi]
 pub mod typescript {
-  use crate::Graph;
+  use crate::{CycleOptions, Graph, GraphComparison};
   use tuan_graph::graph_builders::{self, GraphBuilder as _};
 
+  #[napi(object)]
+  #[derive(Default)]
+  pub struct TypescriptOptions {
+    /// Only files whose project-relative path matches one of these globs are part of the
+    /// graph. Defaults to every file.
+    pub include: Option<Vec<String>>,
+    /// Files and directories whose project-relative path matches one of these globs are
+    /// skipped. Defaults to `dist`, `build`, `out`, `coverage`, `.next`, `.svelte-kit`, `.astro`
+    /// and `storybook-static` directories. `node_modules` and `.git` are always skipped.
+    pub exclude: Option<Vec<String>>,
+    /// Extensions parsed on top of `ts`, `tsx`, `js`, `jsx`, `mjs`, `cjs`, `vue`, `svelte` and
+    /// `astro`, e.g. `mts`.
+    pub extensions: Option<Vec<String>>,
+    /// Files larger than this many bytes are skipped, e.g. bundled vendor files.
+    pub max_file_size: Option<u32>,
+    pub follow_symlinks: Option<bool>,
+    /// Whether `.d.ts` files are part of the graph. Defaults to `true`.
+    pub include_declarations: Option<bool>,
+    /// Adds a node per npm package imported from `node_modules`, instead of dropping those
+    /// imports.
+    pub external_packages: Option<bool>,
+    /// Adds stylesheets to the graph along with their `@import`, `@use`, `@forward` and `url()`
+    /// references, and a node per imported image, font, JSON file or other non-code file.
+    pub assets: Option<bool>,
+  }
+
+  impl TypescriptOptions {
+    fn into_native(self) -> graph_builders::TypescriptOptions {
+      let defaults = graph_builders::TypescriptOptions::default();
+      graph_builders::TypescriptOptions {
+        include: self.include.unwrap_or(defaults.include),
+        exclude: self.exclude.unwrap_or(defaults.exclude),
+        extensions: self.extensions.unwrap_or(defaults.extensions),
+        max_file_size: self.max_file_size.map(u64::from),
+        follow_symlinks: self.follow_symlinks.unwrap_or(defaults.follow_symlinks),
+        include_declarations: self
+          .include_declarations
+          .unwrap_or(defaults.include_declarations),
+        external_packages: self.external_packages.unwrap_or(defaults.external_packages),
+        assets: self.assets.unwrap_or(defaults.assets),
+      }
+    }
+  }
+
+  /// When `cacheDir` is given (e.g. `<projectPath>/.tuan`), imports of unchanged files are
+  /// read from the cache stored there instead of being parsed again.
   #[allow(dead_code)]
   #[napi]
-  pub fn get_graph(project_path: String) -> Graph {
+  pub fn get_graph(
+    project_path: String,
+    cache_dir: Option<String>,
+    options: Option<TypescriptOptions>,
+  ) -> Graph {
     let path = std::path::PathBuf::from(project_path);
-    let builder = graph_builders::Typescript::new(path);
+    let options = options.unwrap_or_default().into_native();
+    let builder = match cache_dir {
+      Some(cache_dir) => graph_builders::Typescript::with_cache(path, cache_dir.into(), options),
+      None => graph_builders::Typescript::with_options(path, options),
+    };
     let graph = builder.get_graph();
-    Graph { inner: graph }
+    Graph::new(graph, Some(builder))
+  }
+
+  /// Builds the graph of `projectPath` at the `base` and `head` git revisions, each checked out
+  /// in a temporary worktree, and compares them.
+  #[allow(dead_code)]
+  #[napi]
+  pub fn compare_revisions(
+    project_path: String,
+    base: String,
+    head: String,
+    options: Option<TypescriptOptions>,
+    cycle_options: Option<CycleOptions>,
+  ) -> napi::Result<GraphComparison> {
+    let comparison = graph_builders::Typescript::compare_revisions(
+      std::path::Path::new(&project_path),
+      &base,
+      &head,
+      options.unwrap_or_default().into_native(),
+      cycle_options
+        .map(CycleOptions::into_native)
+        .unwrap_or_default(),
+    )
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    Ok(GraphComparison::from_native(comparison))
   }
 }
@@ -1,4 +1,5 @@
 import path from 'node:path';
+import { mkdirSync, mkdtempSync, realpathSync, rmSync, writeFileSync } from 'node:fs';
 import { execSync } from 'node:child_process';
 import { tmpdir } from 'node:os';
 import anyTest, { type TestFn } from 'ava'
@@ -198,3 +199,35 @@ test('sync function from native code', (t) => {
 
   t.assert(true);
 })
+
+test('labeler from project', (t) => {
+  const { projectDir } = t.context;
+
+  const projectLabeler = ProjectLabeler.fromProject(projectDir)
+  const result = projectLabeler.labelFiles([
+    `${projectDir}/packages/core/src/core.ts`,
+    `${projectDir}/packages/core/src/client/create-route.ts`,
+  ])
+
+  t.true(Object.keys(result).length > 0)
+})
+
+test('labeler from project skips ignored files', (t) => {
+  const projectDir = realpathSync(mkdtempSync(path.join(tmpdir(), 'tuan-labeler-')))
+  try {
+    const write = (file: string, content = '') => {
+      mkdirSync(path.dirname(path.join(projectDir, file)), { recursive: true })
+      writeFileSync(path.join(projectDir, file), content)
+    }
+    write('.gitignore', 'generated/\n')
+    write('.tuanignore', 'src/scratch.ts\n')
+    write('src/index.ts', `export const index = 1\n`)
+    write('src/scratch.ts', `export const scratch = 1\n`)
+    write('generated/client.ts', `export const client = 1\n`)
+
+    const projectLabeler = ProjectLabeler.fromProject(projectDir)
+    t.deepEqual(projectLabeler.files(), [path.join(projectDir, 'src/index.ts')])
+  } finally {
+    rmSync(projectDir, { recursive: true, force: true })
+  }
+})
@@ -2,5 +2,20 @@
 /* eslint-disable */
 export declare class ProjectLabeler {
   constructor(projectPath: string, projectFiles: Array<string>)
+  /**
+   * Labels the source files of `projectPath` that are not ignored by `.gitignore`, `.ignore`
+   * or `.tuanignore` files, the same files the graph is built from with the same options.
+   */
+  static fromProject(projectPath: string, options?: ProjectOptions | undefined | null): ProjectLabeler
+  /** The project files labels are scored against. */
+  files(): Array<string>
   labelFiles(selectedFiles: Array<string>): Record<string, number>
 }
+
+/** The file discovery options of `typescript.getGraph`. */
+export interface ProjectOptions {
+  include?: Array<string>
+  exclude?: Array<string>
+  extensions?: Array<string>
+  includeDeclarations?: boolean
+}
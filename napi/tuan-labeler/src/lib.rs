@@ -21,6 +21,29 @@ impl ProjectLabeler {
     }
   }
 
+  /// Labels the source files of `projectPath` that are not ignored by `.gitignore`, `.ignore`
+  /// or `.tuanignore` files, the same files the graph is built from with the same options.
+  #[napi(factory)]
+  pub fn from_project(project_path: String, options: Option<ProjectOptions>) -> Self {
+    Self {
+      inner: tuan_labeler::ProjectLabeler::from_project_with_options(
+        project_path,
+        options.unwrap_or_default().into_native(),
+      ),
+    }
+  }
+
+  /// The project files labels are scored against.
+  #[napi]
+  pub fn files(&self) -> Vec<String> {
+    self
+      .inner
+      .files()
+      .iter()
+      .map(|file| file.to_string_lossy().to_string())
+      .collect()
+  }
+
   #[napi]
   pub fn label_files(&self, selected_files: Vec<String>) -> HashMap<String, f64> {
     self
@@ -29,3 +52,27 @@ impl ProjectLabeler {
       .unwrap()
   }
 }
+
+/// The file discovery options of `typescript.getGraph`.
+#[napi(object)]
+#[derive(Default)]
+pub struct ProjectOptions {
+  pub include: Option<Vec<String>>,
+  pub exclude: Option<Vec<String>>,
+  pub extensions: Option<Vec<String>>,
+  pub include_declarations: Option<bool>,
+}
+
+impl ProjectOptions {
+  fn into_native(self) -> tuan_labeler::ProjectOptions {
+    let defaults = tuan_labeler::ProjectOptions::default();
+    tuan_labeler::ProjectOptions {
+      include: self.include.unwrap_or(defaults.include),
+      exclude: self.exclude.unwrap_or(defaults.exclude),
+      extensions: self.extensions.unwrap_or(defaults.extensions),
+      include_declarations: self
+        .include_declarations
+        .unwrap_or(defaults.include_declarations),
+    }
+  }
+}